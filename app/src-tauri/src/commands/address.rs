@@ -1,12 +1,44 @@
 // Address derivation commands
 
 use tauri::State;
-use crate::types::AddressInfo;
+use bitcoin::{Address, Network};
+use std::str::FromStr;
+use crate::types::{AddressInfo, WatchOnlyDescriptor};
 use crate::state::WalletState;
 use crate::wallet::hd::HDWallet;
-use crate::wallet::address::{bip84_path, derive_address_from_key, ScriptType};
+use crate::wallet::address::{bip84_path, derivation_path, derive_address_from_key, script_type_from_path, ScriptType};
+use crate::wallet::descriptor::{derive_address_from_descriptor, DescriptorWallet};
+use crate::wallet::verify::DEFAULT_GAP_LIMIT;
 use crate::crypto::keys::parse_derivation_path;
 
+/// Parse a script type string ("p2pkh"/"legacy", "p2sh-p2wpkh"/"nested_segwit",
+/// "p2wpkh"/"native_segwit", or "p2tr"/"taproot")
+fn parse_script_type(script_type: &str) -> Result<ScriptType, String> {
+    match script_type.to_lowercase().as_str() {
+        "p2pkh" | "legacy" => Ok(ScriptType::Legacy),
+        "p2sh-p2wpkh" | "p2sh_p2wpkh" | "nested_segwit" => Ok(ScriptType::NestedSegwit),
+        "p2wpkh" | "native_segwit" | "segwit" => Ok(ScriptType::NativeSegwit),
+        "p2tr" | "taproot" => Ok(ScriptType::Taproot),
+        _ => Err(format!("Unsupported script type: {}", script_type)),
+    }
+}
+
+/// Map a BIP44/49/84/86 purpose number to its script type
+fn parse_purpose(purpose: u32) -> Result<ScriptType, String> {
+    ScriptType::from_purpose(purpose).map_err(|e| e.to_string())
+}
+
+/// Parse a network string the same way the wallet management commands do
+fn parse_network(network: &str) -> Result<Network, String> {
+    match network.to_lowercase().as_str() {
+        "bitcoin" | "mainnet" => Ok(Network::Bitcoin),
+        "testnet" => Ok(Network::Testnet),
+        "regtest" => Ok(Network::Regtest),
+        "signet" => Ok(Network::Signet),
+        _ => Err(format!("Invalid network: {}", network)),
+    }
+}
+
 #[tauri::command]
 pub async fn derive_address(
     account: u32,
@@ -60,8 +92,8 @@ pub async fn derive_custom_address(
     let mnemonic = unlocked.mnemonic.clone();
     let network = unlocked.network;
 
-    // Validate derivation path
-    parse_derivation_path(&derivation_path)
+    // Validate derivation path and read its purpose component
+    let parsed_path = parse_derivation_path(&derivation_path)
         .map_err(|e| format!("Invalid derivation path: {}", e))?;
 
     // Create HD wallet from mnemonic
@@ -75,12 +107,10 @@ pub async fn derive_custom_address(
     let key = wallet.derive_key_from_path(&derivation_path)
         .map_err(|e| format!("Failed to derive key: {}", e))?;
 
-    // Determine script type from path (default to Native SegWit)
-    let script_type = if derivation_path.starts_with("m/86'") || derivation_path.starts_with("86'") {
-        ScriptType::Taproot
-    } else {
-        ScriptType::NativeSegwit
-    };
+    // The purpose component of the path drives the script type, so path and
+    // script type can never disagree (falls back to Native SegWit for
+    // non-standard purposes)
+    let script_type = script_type_from_path(&parsed_path);
 
     // Derive address
     let address_info = derive_address_from_key(
@@ -148,6 +178,52 @@ pub async fn derive_addresses(
     Ok(addresses)
 }
 
+/// Derive an address at an explicit BIP44/49/84/86 purpose, account, change
+/// and index, so callers aren't limited to `derive_address`'s hardcoded
+/// Native SegWit path
+#[tauri::command]
+pub async fn derive_account(
+    purpose: u32,
+    account: u32,
+    change: u32,
+    index: u32,
+    state: State<'_, WalletState>,
+) -> Result<AddressInfo, String> {
+    // Check if wallet is unlocked
+    let unlocked = state.get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let script_type = parse_purpose(purpose)?;
+    let mnemonic = unlocked.mnemonic.clone();
+    let network = unlocked.network;
+
+    // Create HD wallet from mnemonic
+    let wallet = HDWallet::from_mnemonic(
+        &mnemonic,
+        None,
+        network,
+    ).map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    // Generate the purpose-specific derivation path
+    let path = derivation_path(script_type, account, change, index, network);
+
+    // Derive key at path
+    let key = wallet.derive_key_from_path(&path)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+
+    // Derive address
+    let address_info = derive_address_from_key(
+        &key,
+        script_type,
+        &path,
+        network,
+    ).map_err(|e| format!("Failed to derive address: {}", e))?;
+
+    state.update_activity();
+
+    Ok(address_info)
+}
+
 #[tauri::command]
 pub async fn validate_derivation_path(path: String) -> Result<bool, String> {
     match parse_derivation_path(&path) {
@@ -155,3 +231,118 @@ pub async fn validate_derivation_path(path: String) -> Result<bool, String> {
         Err(_) => Ok(false),
     }
 }
+
+/// Export the account-level xpub and watch-only output descriptors for a script type
+#[tauri::command]
+pub async fn export_descriptor(
+    account: u32,
+    script_type: String,
+    state: State<'_, WalletState>,
+) -> Result<WatchOnlyDescriptor, String> {
+    // Check if wallet is unlocked
+    let unlocked = state.get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let script_type = parse_script_type(&script_type)?;
+
+    // Create HD wallet from mnemonic
+    let wallet = HDWallet::from_mnemonic(
+        &unlocked.mnemonic,
+        None,
+        unlocked.network,
+    ).map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    let (account_xpub, receive, change) = wallet
+        .export_account_descriptors(script_type, account)
+        .map_err(|e| format!("Failed to export descriptors: {}", e))?;
+
+    state.update_activity();
+
+    Ok(WatchOnlyDescriptor {
+        receive,
+        change,
+        account_xpub,
+        fingerprint: wallet.fingerprint(),
+    })
+}
+
+/// Same as `export_descriptor`, but selects the script type by BIP44/49/84/86
+/// purpose number rather than by name
+#[tauri::command]
+pub async fn account_descriptor(
+    purpose: u32,
+    account: u32,
+    state: State<'_, WalletState>,
+) -> Result<WatchOnlyDescriptor, String> {
+    // Check if wallet is unlocked
+    let unlocked = state.get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let script_type = parse_purpose(purpose)?;
+
+    // Create HD wallet from mnemonic
+    let wallet = HDWallet::from_mnemonic(
+        &unlocked.mnemonic,
+        None,
+        unlocked.network,
+    ).map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    let (account_xpub, receive, change) = wallet
+        .export_account_descriptors(script_type, account)
+        .map_err(|e| format!("Failed to export descriptors: {}", e))?;
+
+    state.update_activity();
+
+    Ok(WatchOnlyDescriptor {
+        receive,
+        change,
+        account_xpub,
+        fingerprint: wallet.fingerprint(),
+    })
+}
+
+/// Derive a concrete address at `index` from an arbitrary output descriptor string
+#[tauri::command]
+pub async fn derive_from_descriptor(
+    descriptor: String,
+    index: u32,
+    network: String,
+) -> Result<AddressInfo, String> {
+    let network = parse_network(&network)?;
+
+    derive_address_from_descriptor(&descriptor, index, network)
+        .map_err(|e| format!("Failed to derive address from descriptor: {}", e))
+}
+
+/// Whether `address` belongs to the wallet's currently configured active
+/// descriptor (see `set_active_descriptor`), scanning both the receive and
+/// change branches up to the default gap limit. Useful for confirming a
+/// receive address shown on an air-gapped device actually matches what the
+/// watch-only host displays, without re-entering the mnemonic.
+#[tauri::command]
+pub async fn verify_receive_address(
+    address: String,
+    state: State<'_, WalletState>,
+) -> Result<bool, String> {
+    let unlocked = state
+        .get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let network = unlocked.network;
+    let (receive, change) = unlocked
+        .active_descriptor
+        .ok_or_else(|| "No active descriptor configured".to_string())?;
+
+    let descriptor_wallet = DescriptorWallet::new(receive, change, network)
+        .map_err(|e| format!("Invalid active descriptor: {}", e))?;
+
+    let script_pubkey = Address::from_str(&address)
+        .map_err(|e| format!("Invalid address: {}", e))?
+        .require_network(network)
+        .map_err(|e| format!("Address does not match wallet network: {}", e))?
+        .script_pubkey();
+
+    state.update_activity();
+
+    Ok(descriptor_wallet.address_belongs_to_wallet(&script_pubkey, DEFAULT_GAP_LIMIT))
+}
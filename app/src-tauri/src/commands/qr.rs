@@ -6,8 +6,11 @@ use base64::{Engine as _, engine::general_purpose};
 use flate2::write::GzEncoder;
 use flate2::read::GzDecoder;
 use flate2::Compression;
+use hex::FromHex;
 use std::io::{Write, Read};
 
+use crate::wallet::ur;
+
 /// Compress data using gzip
 fn compress_data(data: &[u8]) -> Result<Vec<u8>, String> {
     let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
@@ -29,16 +32,10 @@ fn decompress_data(data: &[u8]) -> Result<Vec<u8>, String> {
     Ok(decompressed)
 }
 
-/// Generate QR code from data and return as base64-encoded PNG
-#[tauri::command]
-pub fn generate_qr(data: String, size: u32) -> Result<String, String> {
-    // Validate size
-    if size == 0 || size > 2048 {
-        return Err("Size must be between 1 and 2048".to_string());
-    }
-
+/// Render `data` as a QR code and return it as a base64-encoded PNG data URL
+fn render_qr_data_url(data: &[u8], size: u32) -> Result<String, String> {
     // Generate QR code
-    let qr_code = QrCode::new(data.as_bytes())
+    let qr_code = QrCode::new(data)
         .map_err(|e| format!("Failed to generate QR code: {}", e))?;
 
     // Render to image
@@ -66,6 +63,17 @@ pub fn generate_qr(data: String, size: u32) -> Result<String, String> {
     Ok(format!("data:image/png;base64,{}", base64_image))
 }
 
+/// Generate QR code from data and return as base64-encoded PNG
+#[tauri::command]
+pub fn generate_qr(data: String, size: u32) -> Result<String, String> {
+    // Validate size
+    if size == 0 || size > 2048 {
+        return Err("Size must be between 1 and 2048".to_string());
+    }
+
+    render_qr_data_url(data.as_bytes(), size)
+}
+
 /// Generate QR code with optional compression for large data
 #[tauri::command]
 pub fn generate_qr_compressed(data: String, size: u32, compress: bool) -> Result<String, String> {
@@ -98,33 +106,7 @@ pub fn generate_qr_compressed(data: String, size: u32, compress: bool) -> Result
     // Encode as base64 for QR code
     let base64_data = general_purpose::STANDARD.encode(&qr_data);
 
-    // Generate QR code from base64 string
-    let qr_code = QrCode::new(base64_data.as_bytes())
-        .map_err(|e| format!("Failed to generate QR code: {}", e))?;
-
-    // Render to image
-    let qr_image = qr_code.render::<Luma<u8>>()
-        .min_dimensions(size, size)
-        .max_dimensions(size, size)
-        .build();
-
-    // Convert to DynamicImage
-    let dynamic_image = DynamicImage::ImageLuma8(qr_image);
-
-    // Encode as PNG
-    let mut png_bytes: Vec<u8> = Vec::new();
-    dynamic_image
-        .write_to(
-            &mut std::io::Cursor::new(&mut png_bytes),
-            image::ImageFormat::Png,
-        )
-        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
-
-    // Encode as base64
-    let base64_image = general_purpose::STANDARD.encode(&png_bytes);
-
-    // Return as data URL
-    Ok(format!("data:image/png;base64,{}", base64_image))
+    render_qr_data_url(base64_data.as_bytes(), size)
 }
 
 /// Decode compressed QR data
@@ -148,6 +130,127 @@ pub fn decode_compressed_qr(data: String) -> Result<String, String> {
     }
 }
 
+/// Decode a PSBT string in the given format ("base64" or "hex") to raw bytes
+fn decode_psbt_payload(data: &str, format: &str) -> Result<Vec<u8>, String> {
+    match format.to_lowercase().as_str() {
+        "base64" => general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| format!("Invalid base64: {}", e)),
+        "hex" => Vec::from_hex(data).map_err(|e| format!("Invalid hex: {}", e)),
+        _ => Err(format!("Unsupported format: {}", format)),
+    }
+}
+
+/// Split a PSBT into an animated BC-UR style stream of fountain-coded parts,
+/// each small enough to fit one QR frame (`ur:crypto-psbt/<seq>-<seqLen>/<bytewords>`)
+#[tauri::command]
+pub fn generate_ur_parts(psbt: String, format: String, max_fragment_len: u32) -> Result<Vec<String>, String> {
+    if max_fragment_len == 0 {
+        return Err("max_fragment_len must be greater than 0".to_string());
+    }
+
+    let bytes = decode_psbt_payload(&psbt, &format)?;
+    Ok(ur::generate_ur_parts(&bytes, ur::CRYPTO_PSBT_TYPE, max_fragment_len as usize))
+}
+
+/// Pull `count` more UR parts continuing the sequence at `start_seq`, for
+/// when an initial `generate_ur_parts` batch wasn't enough to reconstruct the
+/// PSBT — the fountain stream is unbounded, so this asks it for further
+/// redundancy instead of starting the whole transfer over.
+#[tauri::command]
+pub fn generate_more_ur_parts(
+    psbt: String,
+    format: String,
+    max_fragment_len: u32,
+    start_seq: u32,
+    count: u32,
+) -> Result<Vec<String>, String> {
+    if max_fragment_len == 0 {
+        return Err("max_fragment_len must be greater than 0".to_string());
+    }
+
+    let bytes = decode_psbt_payload(&psbt, &format)?;
+    Ok(ur::generate_ur_parts_from(
+        &bytes,
+        ur::CRYPTO_PSBT_TYPE,
+        max_fragment_len as usize,
+        start_seq,
+        count as usize,
+    ))
+}
+
+/// Reassemble a PSBT from a collection of scanned UR parts and return it as base64
+#[tauri::command]
+pub fn decode_ur_parts(parts: Vec<String>) -> Result<String, String> {
+    let bytes = ur::decode_ur_parts(&parts).map_err(|e| e.to_string())?;
+
+    bitcoin::psbt::Psbt::deserialize(&bytes)
+        .map_err(|e| format!("Decoded UR data is not a valid PSBT: {}", e))?;
+
+    Ok(general_purpose::STANDARD.encode(&bytes))
+}
+
+/// Split arbitrary data into a stream of fountain-coded QR frames, each
+/// rendered as its own base64-encoded PNG, so the frontend can display them
+/// as a loop and a scanner can reassemble the original data from any
+/// sufficient subset. Unlike `generate_ur_parts`, this isn't PSBT-specific:
+/// any string too large for a single QR (e.g. a multi-input PSBT) can be
+/// passed as-is.
+#[tauri::command]
+pub fn generate_qr_animated(data: String, size: u32, max_fragment_len: u32) -> Result<Vec<String>, String> {
+    // Validate size
+    if size == 0 || size > 2048 {
+        return Err("Size must be between 1 and 2048".to_string());
+    }
+    if max_fragment_len == 0 {
+        return Err("max_fragment_len must be greater than 0".to_string());
+    }
+
+    let frames = ur::generate_qr_fountain_frames(data.as_bytes(), max_fragment_len as usize);
+
+    frames
+        .into_iter()
+        .map(|frame| render_qr_data_url(frame.as_bytes(), size))
+        .collect()
+}
+
+/// Pull `count` more animated-QR frames continuing the sequence at
+/// `start_seq`, for when an initial `generate_qr_animated` loop wasn't
+/// enough for the scanner to recover every fragment — the fountain stream is
+/// unbounded, so this asks it for further redundancy rather than restarting
+/// the whole transfer.
+#[tauri::command]
+pub fn generate_more_qr_animated_frames(
+    data: String,
+    size: u32,
+    max_fragment_len: u32,
+    start_seq: u32,
+    count: u32,
+) -> Result<Vec<String>, String> {
+    if size == 0 || size > 2048 {
+        return Err("Size must be between 1 and 2048".to_string());
+    }
+    if max_fragment_len == 0 {
+        return Err("max_fragment_len must be greater than 0".to_string());
+    }
+
+    let frames = ur::generate_qr_fountain_frames_from(data.as_bytes(), max_fragment_len as usize, start_seq, count as usize);
+
+    frames
+        .into_iter()
+        .map(|frame| render_qr_data_url(frame.as_bytes(), size))
+        .collect()
+}
+
+/// Reassemble data from a collection of scanned `generate_qr_animated` frames
+/// (the base64 text each QR frame encodes, as read by a scanner)
+#[tauri::command]
+pub fn decode_qr_animated(parts: Vec<String>) -> Result<String, String> {
+    let bytes = ur::decode_qr_fountain_frames(&parts).map_err(|e| e.to_string())?;
+
+    String::from_utf8(bytes).map_err(|e| format!("Failed to decode UTF-8: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,4 +340,56 @@ mod tests {
         let decoded = decode_compressed_qr(encoded).unwrap();
         assert_eq!(original, decoded);
     }
+
+    #[test]
+    fn test_generate_and_decode_ur_parts_roundtrip() {
+        let psbt_bytes = vec![0x70u8, 0x73, 0x62, 0x74, 0xff].repeat(50);
+        let psbt_base64 = general_purpose::STANDARD.encode(&psbt_bytes);
+
+        let parts = generate_ur_parts(psbt_base64.clone(), "base64".to_string(), 32).unwrap();
+        assert!(parts.iter().all(|p| p.starts_with("ur:crypto-psbt/")));
+
+        // Not a valid PSBT, so reassembly succeeds but the PSBT sanity check fails
+        let result = decode_ur_parts(parts);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_generate_ur_parts_rejects_zero_fragment_len() {
+        let result = generate_ur_parts("AA==".to_string(), "base64".to_string(), 0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_generate_and_decode_qr_animated_roundtrip() {
+        let data = "a sample PSBT payload too large for one QR frame".repeat(20);
+
+        let frames = generate_qr_animated(data.clone(), 200, 32).unwrap();
+        assert!(frames.iter().all(|f| f.starts_with("data:image/png;base64,")));
+
+        // Frames are rendered PNGs, not the raw fountain text; a scanner
+        // would decode each QR's image back to that text before calling
+        // decode_qr_animated, so feed it the frame content directly here.
+        let raw_frames = ur::generate_qr_fountain_frames(data.as_bytes(), 32);
+        let recovered = decode_qr_animated(raw_frames).unwrap();
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn test_generate_qr_animated_rejects_zero_fragment_len() {
+        let result = generate_qr_animated("test".to_string(), 200, 0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_generate_qr_animated_invalid_size() {
+        let result = generate_qr_animated("test".to_string(), 0, 32);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_decode_qr_animated_rejects_empty() {
+        let result = decode_qr_animated(vec![]);
+        assert!(result.is_err());
+    }
 }
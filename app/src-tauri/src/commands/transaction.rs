@@ -2,14 +2,28 @@
 
 use tauri::State;
 use bitcoin::psbt::Psbt;
+use bitcoin::Network;
 use hex::FromHex;
 use base64::{Engine as _, engine::general_purpose};
 
 use crate::types::{PSBTDetails, SignedPSBTResult, TransactionDetails, TxInput, TxOutput};
 use crate::state::WalletState;
 use crate::wallet::hd::HDWallet;
+use crate::wallet::descriptor::DescriptorWallet;
+use crate::wallet::message::{sign_message as sign_message_internal, verify_message as verify_message_internal};
 use crate::wallet::psbt::{parse_psbt as parse_psbt_internal, sign_psbt as sign_psbt_internal};
 
+/// Parse a network string the same way the wallet management commands do
+fn parse_network(network: &str) -> Result<Network, String> {
+    match network.to_lowercase().as_str() {
+        "bitcoin" | "mainnet" => Ok(Network::Bitcoin),
+        "testnet" => Ok(Network::Testnet),
+        "regtest" => Ok(Network::Regtest),
+        "signet" => Ok(Network::Signet),
+        _ => Err(format!("Invalid network: {}", network)),
+    }
+}
+
 /// Parse PSBT and extract details for review
 #[tauri::command]
 pub async fn parse_psbt(
@@ -24,8 +38,20 @@ pub async fn parse_psbt(
 
     let network = unlocked.network;
 
-    // Parse PSBT
-    let details = parse_psbt_internal(&psbt_data, &format, network)
+    let wallet = HDWallet::from_mnemonic(&unlocked.mnemonic, None, network)
+        .map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    // If an output descriptor is configured, also classify inputs/outputs
+    // against it (covers multisig and other non-default script paths)
+    let descriptor_wallet = unlocked
+        .active_descriptor
+        .as_ref()
+        .map(|(receive, change)| DescriptorWallet::new(receive.clone(), change.clone(), network))
+        .transpose()
+        .map_err(|e| format!("Invalid active descriptor: {}", e))?;
+
+    // Parse PSBT (runs the change/fee safety verification pass)
+    let details = parse_psbt_internal(&psbt_data, &format, network, &wallet, descriptor_wallet.as_ref())
         .map_err(|e| format!("Failed to parse PSBT: {}", e))?;
 
     state.update_activity();
@@ -38,6 +64,8 @@ pub async fn parse_psbt(
 pub async fn sign_psbt(
     psbt_data: String,
     format: String,
+    descriptor: Option<String>,
+    force: bool,
     state: State<'_, WalletState>,
 ) -> Result<SignedPSBTResult, String> {
     // Check if wallet is unlocked
@@ -52,8 +80,8 @@ pub async fn sign_psbt(
     let wallet = HDWallet::from_mnemonic(&mnemonic, None, network)
         .map_err(|e| format!("Failed to create wallet: {}", e))?;
 
-    // Sign the PSBT
-    let result = sign_psbt_internal(&psbt_data, &format, &wallet)
+    // Sign the PSBT (refuses when the safety pass raises warnings, unless forced)
+    let result = sign_psbt_internal(&psbt_data, &format, &wallet, descriptor.as_deref(), network, force)
         .map_err(|e| format!("Failed to sign PSBT: {}", e))?;
 
     state.update_activity();
@@ -119,3 +147,40 @@ pub async fn get_transaction_details(
         outputs,
     })
 }
+
+/// Sign a message proving ownership of the address at `derivation_path`, BIP322-simple style
+#[tauri::command]
+pub async fn sign_message(
+    message: String,
+    derivation_path: String,
+    state: State<'_, WalletState>,
+) -> Result<String, String> {
+    let unlocked = state
+        .get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let network = unlocked.network;
+    let wallet = HDWallet::from_mnemonic(&unlocked.mnemonic, None, network)
+        .map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    let signature = sign_message_internal(&wallet, &message, &derivation_path, network)
+        .map_err(|e| format!("Failed to sign message: {}", e))?;
+
+    state.update_activity();
+
+    Ok(signature)
+}
+
+/// Verify a BIP322-simple message signature against an address, independent of wallet state
+#[tauri::command]
+pub fn verify_message(
+    message: String,
+    address: String,
+    signature: String,
+    network: String,
+) -> Result<bool, String> {
+    let network = parse_network(&network)?;
+
+    verify_message_internal(&message, &address, &signature, network)
+        .map_err(|e| format!("Failed to verify message: {}", e))
+}
@@ -1,15 +1,29 @@
 // Wallet management commands
 
+use std::path::PathBuf;
+
 use bitcoin::Network;
 use chrono::Utc;
 use tauri::State;
 
-use crate::types::WalletInfo;
+use crate::types::{WalletInfo, RevealedMnemonic};
 use crate::state::WalletState;
-use crate::crypto::mnemonic::{generate_mnemonic, validate_mnemonic as validate_mnemonic_internal, get_wordlist};
+use crate::crypto::mnemonic::{
+    entropy_to_mnemonic, generate_mnemonic, mnemonic_to_entropy,
+    validate_mnemonic as validate_mnemonic_internal, get_wordlist,
+};
+use crate::crypto::secret::SecretString;
+use crate::crypto::slip39::{export_shamir_shares as export_shamir_shares_internal, import_shamir_shares as import_shamir_shares_internal};
+use crate::wallet::descriptor::{descriptor_matches_fingerprint, parse_descriptor};
 use crate::wallet::hd::HDWallet;
 use crate::storage::wallet_file::WalletPayload;
-use crate::storage::encrypted::{save_wallet, load_wallet, wallet_exists as check_wallet_exists, delete_wallet as delete_wallet_file};
+use crate::storage::encrypted::{
+    save_wallet, load_wallet, wallet_exists as check_wallet_exists,
+    delete_wallet as delete_wallet_file, change_password as change_password_internal,
+    get_wallet_path, export_wallet as export_wallet_internal, import_wallet as import_wallet_internal,
+    export_wallet_sealed as export_wallet_sealed_internal, import_wallet_sealed as import_wallet_sealed_internal,
+};
+use bitcoin::secp256k1::{PublicKey, SecretKey};
 
 /// Parse network string to Network enum
 fn parse_network(network: &str) -> Result<Network, String> {
@@ -39,6 +53,7 @@ pub async fn create_wallet(
     passphrase: Option<String>,
     password: String,
     network: String,
+    idle_timeout_secs: Option<u64>,
     state: State<'_, WalletState>,
 ) -> Result<WalletInfo, String> {
     // Validate word count
@@ -65,8 +80,8 @@ pub async fn create_wallet(
 
     // Create payload
     let payload = WalletPayload {
-        mnemonic: mnemonic.clone(),
-        passphrase: passphrase.clone(),
+        mnemonic: mnemonic.clone().into(),
+        passphrase: passphrase.clone().map(SecretString::from),
         network: network_enum,
         fingerprint: fingerprint.clone(),
         created_at,
@@ -77,7 +92,7 @@ pub async fn create_wallet(
         .map_err(|e| format!("Failed to save wallet: {}", e))?;
 
     // Unlock wallet in state
-    state.unlock(mnemonic.clone(), network_enum, fingerprint.clone());
+    state.unlock(mnemonic.clone(), network_enum, fingerprint.clone(), idle_timeout_secs);
     state.update_activity();
 
     Ok(WalletInfo {
@@ -94,6 +109,7 @@ pub async fn import_wallet(
     passphrase: Option<String>,
     password: String,
     network: String,
+    idle_timeout_secs: Option<u64>,
     state: State<'_, WalletState>,
 ) -> Result<WalletInfo, String> {
     // Validate mnemonic
@@ -119,8 +135,8 @@ pub async fn import_wallet(
 
     // Create payload
     let payload = WalletPayload {
-        mnemonic: mnemonic.clone(),
-        passphrase: passphrase.clone(),
+        mnemonic: mnemonic.clone().into(),
+        passphrase: passphrase.clone().map(SecretString::from),
         network: network_enum,
         fingerprint: fingerprint.clone(),
         created_at,
@@ -131,7 +147,7 @@ pub async fn import_wallet(
         .map_err(|e| format!("Failed to save wallet: {}", e))?;
 
     // Unlock wallet in state
-    state.unlock(mnemonic.clone(), network_enum, fingerprint.clone());
+    state.unlock(mnemonic.clone(), network_enum, fingerprint.clone(), idle_timeout_secs);
     state.update_activity();
 
     Ok(WalletInfo {
@@ -145,6 +161,7 @@ pub async fn import_wallet(
 #[tauri::command]
 pub async fn unlock_wallet(
     password: String,
+    idle_timeout_secs: Option<u64>,
     state: State<'_, WalletState>,
 ) -> Result<WalletInfo, String> {
     // Load wallet from disk
@@ -158,7 +175,7 @@ pub async fn unlock_wallet(
     let mnemonic = payload.mnemonic.clone();
 
     // Unlock wallet in state
-    state.unlock(mnemonic, network, fingerprint.clone());
+    state.unlock(mnemonic, network, fingerprint.clone(), idle_timeout_secs);
     state.update_activity();
 
     Ok(WalletInfo {
@@ -220,13 +237,27 @@ pub async fn export_mnemonic(
     let payload = load_wallet(&password)
         .map_err(|e| format!("Invalid password: {}", e))?;
 
-    let mnemonic = payload.mnemonic.clone();
+    let mnemonic = payload.mnemonic.as_str().to_string();
 
     state.update_activity();
 
     Ok(mnemonic)
 }
 
+/// Decrypt the on-disk wallet file with `password` and return the recovery
+/// phrase, independent of whether the wallet is currently unlocked in
+/// `WalletState`. Never touches the in-memory unlocked state.
+#[tauri::command]
+pub async fn reveal_mnemonic(password: String) -> Result<RevealedMnemonic, String> {
+    let payload = load_wallet(&password)
+        .map_err(|e| format!("Invalid password: {}", e))?;
+
+    Ok(RevealedMnemonic {
+        mnemonic: payload.mnemonic.as_str().to_string(),
+        passphrase: payload.passphrase.map(|p| p.as_str().to_string()),
+    })
+}
+
 #[tauri::command]
 pub async fn validate_mnemonic(mnemonic: String) -> Result<bool, String> {
     validate_mnemonic_internal(&mnemonic)
@@ -256,6 +287,93 @@ pub async fn delete_wallet(state: State<'_, WalletState>) -> Result<(), String>
     Ok(())
 }
 
+/// Split the unlocked wallet's seed entropy into SLIP-39-style Shamir shares
+///
+/// IMPORTANT: these shares are only readable by this wallet's own
+/// `import_shamir_shares` — they use this wallet's BIP-39 wordlist rather
+/// than SLIP-39's canonical one, so they are NOT interoperable with other
+/// SLIP-39 implementations or hardware devices. Anyone distributing shares
+/// geographically (e.g. for inheritance) must keep a copy of, or access to,
+/// this same wallet software alongside each share; handing a share alone to
+/// a different SLIP-39-compatible wallet or device will not recover the seed.
+#[tauri::command]
+pub async fn export_shamir_shares(
+    password: String,
+    threshold: u8,
+    total: u8,
+    passphrase: Option<String>,
+    state: State<'_, WalletState>,
+) -> Result<Vec<String>, String> {
+    if !state.is_unlocked() {
+        return Err("Wallet is locked".to_string());
+    }
+
+    // Verify password by loading wallet, mirroring export_mnemonic
+    let payload = load_wallet(&password).map_err(|e| format!("Invalid password: {}", e))?;
+
+    let entropy = mnemonic_to_entropy(&payload.mnemonic)
+        .map_err(|e| format!("Failed to read seed entropy: {}", e))?;
+
+    let shares = export_shamir_shares_internal(&entropy, threshold, total, passphrase.as_deref())
+        .map_err(|e| format!("Failed to split wallet into Shamir shares: {}", e))?;
+
+    state.update_activity();
+
+    Ok(shares)
+}
+
+/// Reconstruct the seed from a threshold subset of Shamir shares and unlock a wallet from it
+///
+/// Only accepts shares produced by this wallet's own `export_shamir_shares` —
+/// see that command's doc comment for why shares exported here aren't
+/// portable to other SLIP-39 implementations or hardware devices.
+#[tauri::command]
+pub async fn import_shamir_shares(
+    shares: Vec<String>,
+    passphrase: Option<String>,
+    password: String,
+    network: String,
+    idle_timeout_secs: Option<u64>,
+    state: State<'_, WalletState>,
+) -> Result<WalletInfo, String> {
+    let network_enum = parse_network(&network)?;
+
+    let entropy = import_shamir_shares_internal(&shares, passphrase.as_deref())
+        .map_err(|e| format!("Failed to reconstruct seed from shares: {}", e))?;
+
+    let mnemonic = entropy_to_mnemonic(&entropy)
+        .map_err(|e| format!("Failed to rebuild mnemonic from shares: {}", e))?;
+
+    let wallet = HDWallet::from_mnemonic(&mnemonic, None, network_enum)
+        .map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    let fingerprint = wallet.fingerprint();
+    let created_at = Utc::now();
+
+    let payload = WalletPayload {
+        mnemonic: mnemonic.clone().into(),
+        passphrase: None,
+        network: network_enum,
+        fingerprint: fingerprint.clone(),
+        created_at,
+    };
+
+    save_wallet(&payload, &password)
+        .map_err(|e| format!("Failed to save wallet: {}", e))?;
+
+    state.unlock(mnemonic, network_enum, fingerprint.clone(), idle_timeout_secs);
+    state.update_activity();
+
+    Ok(WalletInfo {
+        network,
+        fingerprint,
+        created_at,
+        is_locked: false,
+    })
+}
+
+/// Re-encrypt the wallet file under a new password, atomically and without
+/// ever leaving a half-written file on disk if `old_password` is wrong.
 #[tauri::command]
 pub async fn change_password(
     old_password: String,
@@ -267,14 +385,158 @@ pub async fn change_password(
         return Err("Wallet is locked".to_string());
     }
 
-    // Load wallet with old password to verify it
-    let payload = load_wallet(&old_password)
-        .map_err(|_| "Invalid current password".to_string())?;
+    change_password_internal(&old_password, &new_password)?;
+
+    state.update_activity();
+
+    Ok(())
+}
+
+/// Copy the encrypted wallet file to `dest_path`, e.g. a USB drive, so it
+/// can be carried between machines. Falls back to the default wallet
+/// location if `dest_path` is omitted. `password` only verifies the local
+/// wallet decrypts before it's copied anywhere.
+#[tauri::command]
+pub async fn export_wallet_backup(
+    dest_path: Option<String>,
+    password: String,
+) -> Result<String, String> {
+    let dest = match dest_path {
+        Some(path) => PathBuf::from(path),
+        None => get_wallet_path()?,
+    };
+
+    export_wallet_internal(dest.clone(), &password)
+        .map_err(|e| format!("Failed to export wallet: {}", e))?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Import an encrypted wallet backup from `src_path` — e.g. one written by
+/// `export_wallet_backup` on another machine — into the default wallet
+/// location. Falls back to reading from the default location if `src_path`
+/// is omitted. Refuses to overwrite an existing local wallet unless
+/// `overwrite` is set.
+///
+/// If `expected_fingerprint` is given (a hex master fingerprint, e.g. copied
+/// from this wallet's exported xpub/descriptor on the other device), the
+/// backup's content signature must recover to it — catching a complete
+/// backup substituted from a different wallet, not just a spliced one.
+#[tauri::command]
+pub async fn import_wallet_backup(
+    src_path: Option<String>,
+    password: String,
+    overwrite: bool,
+    expected_fingerprint: Option<String>,
+) -> Result<(), String> {
+    let src = match src_path {
+        Some(path) => PathBuf::from(path),
+        None => get_wallet_path()?,
+    };
+
+    let expected_fingerprint = expected_fingerprint
+        .map(|hex_str| parse_fingerprint_hex(&hex_str))
+        .transpose()?;
+
+    import_wallet_internal(src, &password, overwrite, expected_fingerprint)
+        .map_err(|e| format!("Failed to import wallet: {}", e))?;
+
+    Ok(())
+}
+
+/// Parse a hex-encoded 4-byte master fingerprint, e.g. `"73c5da0a"`
+fn parse_fingerprint_hex(hex_str: &str) -> Result<[u8; 4], String> {
+    let bytes = hex::decode(hex_str).map_err(|e| format!("Invalid fingerprint hex: {}", e))?;
+    bytes
+        .try_into()
+        .map_err(|_| "Fingerprint must be 4 bytes (8 hex characters)".to_string())
+}
+
+/// Seal the local wallet to `recipient_pubkey_hex` (33-byte compressed
+/// secp256k1 public key, hex-encoded) and write it to `dest_path`, e.g. for
+/// an inheritance backup or pairing a second device that holds only a
+/// keypair rather than this wallet's password.
+#[tauri::command]
+pub async fn export_wallet_sealed(
+    dest_path: Option<String>,
+    password: String,
+    recipient_pubkey_hex: String,
+) -> Result<String, String> {
+    let dest = match dest_path {
+        Some(path) => PathBuf::from(path),
+        None => get_wallet_path()?.with_extension("sealed"),
+    };
+    let recipient_pubkey = parse_pubkey_hex(&recipient_pubkey_hex)?;
+
+    export_wallet_sealed_internal(dest.clone(), &password, &recipient_pubkey)
+        .map_err(|e| format!("Failed to export sealed wallet: {}", e))?;
+
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Recover a wallet sealed by `export_wallet_sealed` using the recipient's
+/// private key, then save it locally under `new_password` — the sealed
+/// backup carries no password of its own, so one is chosen here. Refuses to
+/// overwrite an existing local wallet unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_wallet_sealed(
+    src_path: String,
+    recipient_secret_key_hex: String,
+    new_password: String,
+    overwrite: bool,
+) -> Result<(), String> {
+    if check_wallet_exists() && !overwrite {
+        return Err("Wallet already exists".to_string());
+    }
+
+    let recipient_secret_key = parse_secret_key_hex(&recipient_secret_key_hex)?;
+
+    let payload = import_wallet_sealed_internal(PathBuf::from(src_path), &recipient_secret_key)
+        .map_err(|e| format!("Failed to import sealed wallet: {}", e))?;
+
+    save_wallet(&payload, &new_password).map_err(|e| format!("Failed to save imported wallet: {}", e))?;
 
-    // Save wallet with new password
-    save_wallet(&payload, &new_password)
-        .map_err(|e| format!("Failed to save wallet with new password: {}", e))?;
+    Ok(())
+}
+
+/// Parse a hex-encoded 33-byte compressed secp256k1 public key
+fn parse_pubkey_hex(hex_str: &str) -> Result<PublicKey, String> {
+    let bytes = hex::decode(hex_str).map_err(|e| format!("Invalid public key hex: {}", e))?;
+    PublicKey::from_slice(&bytes).map_err(|e| format!("Invalid public key: {}", e))
+}
+
+/// Parse a hex-encoded 32-byte secp256k1 private key
+fn parse_secret_key_hex(hex_str: &str) -> Result<SecretKey, String> {
+    let bytes = hex::decode(hex_str).map_err(|e| format!("Invalid private key hex: {}", e))?;
+    SecretKey::from_slice(&bytes).map_err(|e| format!("Invalid private key: {}", e))
+}
+
+/// Configure the receive/change output descriptors this wallet is tracked
+/// under, so multisig or other non-default script paths can be recognized
+/// in `parse_psbt` and `verify_receive_address` alongside the implicit
+/// BIP44/49/84/86 paths. Both descriptors must parse and contain a key
+/// derived from this wallet's master fingerprint.
+#[tauri::command]
+pub async fn set_active_descriptor(
+    receive: String,
+    change: String,
+    state: State<'_, WalletState>,
+) -> Result<(), String> {
+    let unlocked = state
+        .get_unlocked()
+        .ok_or_else(|| "Wallet is locked".to_string())?;
+
+    let wallet = HDWallet::from_mnemonic(&unlocked.mnemonic, None, unlocked.network)
+        .map_err(|e| format!("Failed to create wallet: {}", e))?;
+
+    for descriptor in [&receive, &change] {
+        let parsed = parse_descriptor(descriptor).map_err(|e| format!("Invalid descriptor: {}", e))?;
+        if !descriptor_matches_fingerprint(&parsed, wallet.fingerprint_bytes()) {
+            return Err("Descriptor does not contain a key for this wallet".to_string());
+        }
+    }
 
+    state.set_active_descriptor(receive, change);
     state.update_activity();
 
     Ok(())
@@ -0,0 +1,139 @@
+// ECIES: seal data to a recipient's secp256k1 public key
+//
+// Layered on top of the existing password-based AEAD path: an ephemeral
+// keypair is generated per call, ECDH'd against the recipient's public key,
+// and the shared secret is stretched through HKDF-SHA256 into the same
+// 32-byte key `encrypt`/`decrypt` already expect. Useful for sealing a
+// backup to a recipient who holds only a keypair — e.g. an inheritance
+// backup, or a second device pairing without a shared password.
+//
+// Wire format: ephemeral_pubkey (33 bytes, compressed) || nonce || ciphertext || auth_tag
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use bitcoin::secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+
+use crate::crypto::encryption::{decrypt, encrypt, EncryptionKey};
+use crate::error::WalletError;
+
+/// Compressed secp256k1 public key length, and the size of the ephemeral
+/// public key prefix on a sealed blob.
+const EPHEMERAL_PUBKEY_LEN: usize = 33;
+
+/// Domain-separation info string for the HKDF-SHA256 expand step.
+const HKDF_INFO: &[u8] = b"cold-usb/ecies/v1";
+
+/// Seal `data` to `recipient_pubkey`. Only the holder of the matching
+/// private key can recover it via `ecies_decrypt`.
+pub fn ecies_encrypt(data: &[u8], recipient_pubkey: &PublicKey) -> Result<Vec<u8>, WalletError> {
+    let secp = Secp256k1::new();
+
+    let mut ephemeral_sk_bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut ephemeral_sk_bytes);
+    let ephemeral_sk = SecretKey::from_slice(&ephemeral_sk_bytes)
+        .map_err(|e| WalletError::CryptoError(format!("Failed to generate ephemeral key: {}", e)))?;
+    let ephemeral_pk = PublicKey::from_secret_key(&secp, &ephemeral_sk);
+
+    let shared_point = recipient_pubkey
+        .mul_tweak(&secp, &Scalar::from(ephemeral_sk))
+        .map_err(|e| WalletError::CryptoError(format!("ECDH failed: {}", e)))?;
+    let key = EncryptionKey::from_raw(derive_key(&shared_point));
+
+    let ciphertext = encrypt(data, &key)?;
+
+    let mut sealed = Vec::with_capacity(EPHEMERAL_PUBKEY_LEN + ciphertext.len());
+    sealed.extend_from_slice(&ephemeral_pk.serialize());
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Reverse `ecies_encrypt` with the recipient's private key.
+pub fn ecies_decrypt(sealed: &[u8], recipient_secret: &SecretKey) -> Result<Vec<u8>, WalletError> {
+    if sealed.len() < EPHEMERAL_PUBKEY_LEN {
+        return Err(WalletError::CryptoError("Sealed data too short".to_string()));
+    }
+
+    let secp = Secp256k1::new();
+    let ephemeral_pk = PublicKey::from_slice(&sealed[..EPHEMERAL_PUBKEY_LEN])
+        .map_err(|e| WalletError::CryptoError(format!("Invalid ephemeral public key: {}", e)))?;
+
+    let shared_point = ephemeral_pk
+        .mul_tweak(&secp, &Scalar::from(*recipient_secret))
+        .map_err(|e| WalletError::CryptoError(format!("ECDH failed: {}", e)))?;
+    let key = EncryptionKey::from_raw(derive_key(&shared_point));
+
+    decrypt(&sealed[EPHEMERAL_PUBKEY_LEN..], &key)
+}
+
+/// Derive the 32-byte AEAD key from an ECDH shared point: HKDF-SHA256 over
+/// the point's serialized x-only coordinate, with a single expand block
+/// (the 32-byte output fits in one HMAC block, so extract+expand collapses
+/// to two HMAC calls).
+fn derive_key(shared_point: &PublicKey) -> [u8; 32] {
+    let shared_x = shared_point.x_only_public_key().0.serialize();
+
+    let mut extract_engine = hmac::HmacEngine::<sha256::Hash>::new(&[0u8; 32]);
+    extract_engine.input(&shared_x);
+    let prk = hmac::Hmac::<sha256::Hash>::from_engine(extract_engine);
+
+    let mut expand_engine = hmac::HmacEngine::<sha256::Hash>::new(prk.as_byte_array());
+    expand_engine.input(HKDF_INFO);
+    expand_engine.input(&[0x01]);
+    let okm = hmac::Hmac::<sha256::Hash>::from_engine(expand_engine);
+
+    *okm.as_byte_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ecies_encrypt_decrypt_roundtrip() {
+        let secp = Secp256k1::new();
+        let recipient_sk = SecretKey::from_slice(&[9u8; 32]).unwrap();
+        let recipient_pk = PublicKey::from_secret_key(&secp, &recipient_sk);
+
+        let data = b"seal this to the recipient";
+        let sealed = ecies_encrypt(data, &recipient_pk).unwrap();
+
+        let decrypted = ecies_decrypt(&sealed, &recipient_sk).unwrap();
+        assert_eq!(&decrypted, data);
+    }
+
+    #[test]
+    fn test_ecies_decrypt_with_wrong_key_fails() {
+        let secp = Secp256k1::new();
+        let recipient_sk = SecretKey::from_slice(&[9u8; 32]).unwrap();
+        let recipient_pk = PublicKey::from_secret_key(&secp, &recipient_sk);
+        let wrong_sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+
+        let sealed = ecies_encrypt(b"secret payload", &recipient_pk).unwrap();
+
+        let result = ecies_decrypt(&sealed, &wrong_sk);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ecies_ciphertexts_are_not_deterministic() {
+        let secp = Secp256k1::new();
+        let recipient_sk = SecretKey::from_slice(&[9u8; 32]).unwrap();
+        let recipient_pk = PublicKey::from_secret_key(&secp, &recipient_sk);
+
+        let sealed_a = ecies_encrypt(b"same message", &recipient_pk).unwrap();
+        let sealed_b = ecies_encrypt(b"same message", &recipient_pk).unwrap();
+
+        // Fresh ephemeral key + nonce each call, so repeated encryption of
+        // the same message never produces the same bytes
+        assert_ne!(sealed_a, sealed_b);
+    }
+
+    #[test]
+    fn test_ecies_rejects_truncated_input() {
+        let recipient_sk = SecretKey::from_slice(&[9u8; 32]).unwrap();
+
+        let result = ecies_decrypt(&[0u8; 10], &recipient_sk);
+        assert!(result.is_err());
+    }
+}
@@ -1,16 +1,32 @@
-// AES-256-GCM encryption with Argon2id key derivation
+// AEAD encryption with Argon2id key derivation
+//
+// Both the KDF cost parameters and the AEAD algorithm are crypto-agile: they
+// travel with the ciphertext (see `storage::wallet_file::WalletFile`) so
+// `save_wallet` can target a stronger parameter set or a different cipher
+// without breaking `load_wallet`'s ability to open files written under an
+// older one.
+
+use std::time::{Duration, Instant};
 
-use aes_gcm::{
-    aead::{Aead, KeyInit, OsRng},
-    Aes256Gcm, Nonce,
-};
 use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{aead::{Aead, KeyInit, OsRng}, Aes256Gcm, Nonce as GcmNonce};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce as SivNonce};
 use argon2::{Argon2, Algorithm, Version, Params};
+use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
 use crate::error::WalletError;
 
-/// Argon2id parameters for key derivation
+/// Floor and ceiling for `Argon2Params::calibrate`'s `memory_cost` search, in
+/// KiB: 8 MB is cheap enough to probe quickly, 2 GB is well past what's
+/// reasonable to ask of a hardware wallet's host machine.
+const CALIBRATION_MIN_MEMORY_COST: u32 = 8 * 1024;
+const CALIBRATION_MAX_MEMORY_COST: u32 = 2 * 1024 * 1024;
+
+/// Argon2id parameters for key derivation. Stored alongside the ciphertext so
+/// the cost can be raised over time without losing the ability to open
+/// wallets written under a cheaper setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Argon2Params {
     pub time_cost: u32,
     pub memory_cost: u32,
@@ -27,16 +43,102 @@ impl Default for Argon2Params {
     }
 }
 
+impl Argon2Params {
+    /// Binary-search `memory_cost` (holding `time_cost`/`parallelism` at
+    /// their defaults) for a parameter set whose derivation takes roughly
+    /// `target` on the current hardware, so a wallet can opt into a
+    /// stronger-than-default KDF cost at creation time. The chosen
+    /// parameters are stored in `WalletFile::kdf`, so a wallet calibrated on
+    /// fast hardware stays decryptable everywhere else.
+    pub fn calibrate(target: Duration) -> Self {
+        let time_cost = Self::default().time_cost;
+        let parallelism = Self::default().parallelism;
+
+        // Double memory_cost until a derivation takes at least `target`,
+        // capped so this terminates even on hardware too slow to ever reach it
+        let mut low = CALIBRATION_MIN_MEMORY_COST;
+        let mut high = low;
+        while Self::measure(time_cost, high, parallelism) < target && high < CALIBRATION_MAX_MEMORY_COST {
+            low = high;
+            high = (high * 2).min(CALIBRATION_MAX_MEMORY_COST);
+        }
+
+        // Binary search the doubling step's range for the smallest memory_cost
+        // that still meets `target`
+        while high - low > CALIBRATION_MIN_MEMORY_COST {
+            let mid = low + (high - low) / 2;
+            if Self::measure(time_cost, mid, parallelism) >= target {
+                high = mid;
+            } else {
+                low = mid;
+            }
+        }
+
+        Self {
+            time_cost,
+            memory_cost: high,
+            parallelism,
+        }
+    }
+
+    /// Time a single key derivation under the given parameters, using a
+    /// throwaway password/salt purely to measure cost.
+    fn measure(time_cost: u32, memory_cost: u32, parallelism: u32) -> Duration {
+        let params = Self { time_cost, memory_cost, parallelism };
+        let salt = [0u8; 32];
+        let start = Instant::now();
+        let _ = EncryptionKey::from_password_with_params("calibration-probe", &salt, &params);
+        start.elapsed()
+    }
+}
+
+/// Selectable AEAD cipher, tagged alongside the ciphertext in `WalletFile`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AeadAlgorithm {
+    /// AES-256-GCM. The long-standing default; catastrophic key+nonce reuse
+    /// (e.g. from a broken RNG) can leak the authentication key.
+    Aes256Gcm,
+    /// AES-256-GCM-SIV: nonce-misuse-resistant: reusing a nonce under the
+    /// same key only reveals whether two messages were equal, never the key.
+    Aes256GcmSiv,
+}
+
+impl AeadAlgorithm {
+    /// Recommended default for newly saved wallets
+    pub fn default_for_new_wallets() -> Self {
+        AeadAlgorithm::Aes256Gcm
+    }
+
+    pub fn nonce_len(self) -> usize {
+        match self {
+            AeadAlgorithm::Aes256Gcm | AeadAlgorithm::Aes256GcmSiv => 12,
+        }
+    }
+
+    pub fn tag_len(self) -> usize {
+        match self {
+            AeadAlgorithm::Aes256Gcm | AeadAlgorithm::Aes256GcmSiv => 16,
+        }
+    }
+}
+
 #[derive(Zeroize, ZeroizeOnDrop)]
 pub struct EncryptionKey {
     key: [u8; 32],
 }
 
 impl EncryptionKey {
-    /// Derive encryption key from password using Argon2id
+    /// Derive encryption key from password using Argon2id with the default cost
     pub fn from_password(password: &str, salt: &[u8]) -> Result<Self, WalletError> {
-        let params = Argon2Params::default();
+        Self::from_password_with_params(password, salt, &Argon2Params::default())
+    }
 
+    /// Derive encryption key from password using Argon2id with explicit cost parameters
+    pub fn from_password_with_params(
+        password: &str,
+        salt: &[u8],
+        params: &Argon2Params,
+    ) -> Result<Self, WalletError> {
         // Create Argon2 parameters
         let argon2_params = Params::new(
             params.memory_cost,
@@ -59,54 +161,98 @@ impl EncryptionKey {
         Ok(Self { key })
     }
 
+    /// Wrap an already-derived 32-byte key, e.g. the output of the ECDH+HKDF
+    /// agreement in `crypto::ecies`, bypassing Argon2 entirely.
+    pub(crate) fn from_raw(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
     /// Get key as slice
     pub fn as_bytes(&self) -> &[u8; 32] {
         &self.key
     }
 }
 
-/// Encrypt data using AES-256-GCM
+/// Encrypt data using AES-256-GCM.
 /// Returns: nonce (12 bytes) + ciphertext + auth_tag (16 bytes)
 pub fn encrypt(data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, WalletError> {
-    let cipher = Aes256Gcm::new_from_slice(key.as_bytes())
-        .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+    encrypt_with_algorithm(data, key, AeadAlgorithm::Aes256Gcm)
+}
+
+/// Decrypt data using AES-256-GCM.
+/// Expects: nonce (12 bytes) + ciphertext + auth_tag (16 bytes)
+pub fn decrypt(encrypted_data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, WalletError> {
+    decrypt_with_algorithm(encrypted_data, key, AeadAlgorithm::Aes256Gcm)
+}
 
-    // Generate random nonce (96 bits = 12 bytes for GCM)
-    let mut nonce_bytes = [0u8; 12];
+/// Encrypt data under the selected AEAD algorithm.
+/// Returns: nonce + ciphertext + auth_tag, with lengths dictated by `algorithm`.
+pub fn encrypt_with_algorithm(
+    data: &[u8],
+    key: &EncryptionKey,
+    algorithm: AeadAlgorithm,
+) -> Result<Vec<u8>, WalletError> {
+    let nonce_len = algorithm.nonce_len();
+    let mut nonce_bytes = vec![0u8; nonce_len];
     OsRng.fill_bytes(&mut nonce_bytes);
-    let nonce = Nonce::from_slice(&nonce_bytes);
 
-    // Encrypt the data
-    let ciphertext = cipher
-        .encrypt(nonce, data)
-        .map_err(|e| WalletError::CryptoError(format!("Encryption failed: {}", e)))?;
+    let ciphertext = match algorithm {
+        AeadAlgorithm::Aes256Gcm => {
+            let cipher = Aes256Gcm::new_from_slice(key.as_bytes())
+                .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+            let nonce = GcmNonce::from_slice(&nonce_bytes);
+            cipher.encrypt(nonce, data)
+                .map_err(|e| WalletError::CryptoError(format!("Encryption failed: {}", e)))?
+        }
+        AeadAlgorithm::Aes256GcmSiv => {
+            let cipher = Aes256GcmSiv::new_from_slice(key.as_bytes())
+                .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+            let nonce = SivNonce::from_slice(&nonce_bytes);
+            cipher.encrypt(nonce, data)
+                .map_err(|e| WalletError::CryptoError(format!("Encryption failed: {}", e)))?
+        }
+    };
 
-    // Combine nonce + ciphertext (ciphertext already includes auth tag)
-    let mut result = Vec::with_capacity(12 + ciphertext.len());
+    let mut result = Vec::with_capacity(nonce_len + ciphertext.len());
     result.extend_from_slice(&nonce_bytes);
     result.extend_from_slice(&ciphertext);
 
     Ok(result)
 }
 
-/// Decrypt data using AES-256-GCM
-/// Expects: nonce (12 bytes) + ciphertext + auth_tag (16 bytes)
-pub fn decrypt(encrypted_data: &[u8], key: &EncryptionKey) -> Result<Vec<u8>, WalletError> {
-    if encrypted_data.len() < 12 + 16 {
+/// Decrypt data under the selected AEAD algorithm.
+/// Expects: nonce + ciphertext + auth_tag, with lengths dictated by `algorithm`.
+pub fn decrypt_with_algorithm(
+    encrypted_data: &[u8],
+    key: &EncryptionKey,
+    algorithm: AeadAlgorithm,
+) -> Result<Vec<u8>, WalletError> {
+    let nonce_len = algorithm.nonce_len();
+    let tag_len = algorithm.tag_len();
+
+    if encrypted_data.len() < nonce_len + tag_len {
         return Err(WalletError::CryptoError("Encrypted data too short".to_string()));
     }
 
-    let cipher = Aes256Gcm::new_from_slice(key.as_bytes())
-        .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+    let nonce_bytes = &encrypted_data[..nonce_len];
+    let ciphertext = &encrypted_data[nonce_len..];
 
-    // Extract nonce and ciphertext
-    let nonce = Nonce::from_slice(&encrypted_data[..12]);
-    let ciphertext = &encrypted_data[12..];
-
-    // Decrypt the data
-    let plaintext = cipher
-        .decrypt(nonce, ciphertext)
-        .map_err(|e| WalletError::CryptoError(format!("Decryption failed: {}", e)))?;
+    let plaintext = match algorithm {
+        AeadAlgorithm::Aes256Gcm => {
+            let cipher = Aes256Gcm::new_from_slice(key.as_bytes())
+                .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+            let nonce = GcmNonce::from_slice(nonce_bytes);
+            cipher.decrypt(nonce, ciphertext)
+                .map_err(|e| WalletError::CryptoError(format!("Decryption failed: {}", e)))?
+        }
+        AeadAlgorithm::Aes256GcmSiv => {
+            let cipher = Aes256GcmSiv::new_from_slice(key.as_bytes())
+                .map_err(|e| WalletError::CryptoError(format!("Failed to create cipher: {}", e)))?;
+            let nonce = SivNonce::from_slice(nonce_bytes);
+            cipher.decrypt(nonce, ciphertext)
+                .map_err(|e| WalletError::CryptoError(format!("Decryption failed: {}", e)))?
+        }
+    };
 
     Ok(plaintext)
 }
@@ -158,4 +304,66 @@ mod tests {
 
         assert_eq!(key1.as_bytes(), key2.as_bytes());
     }
+
+    #[test]
+    fn test_gcm_siv_roundtrip() {
+        let password = "gcm_siv_password";
+        let salt = b"test_salt_16byte";
+        let data = b"Secret data encrypted with a nonce-misuse-resistant cipher";
+
+        let key = EncryptionKey::from_password(password, salt).unwrap();
+
+        let encrypted = encrypt_with_algorithm(data, &key, AeadAlgorithm::Aes256GcmSiv).unwrap();
+        let decrypted = decrypt_with_algorithm(&encrypted, &key, AeadAlgorithm::Aes256GcmSiv).unwrap();
+
+        assert_eq!(&decrypted, data);
+    }
+
+    #[test]
+    fn test_gcm_and_gcm_siv_ciphertexts_are_not_interchangeable() {
+        let password = "cross_algo_password";
+        let salt = b"test_salt_16byte";
+        let data = b"Secret data";
+
+        let key = EncryptionKey::from_password(password, salt).unwrap();
+
+        let encrypted = encrypt_with_algorithm(data, &key, AeadAlgorithm::Aes256Gcm).unwrap();
+        let result = decrypt_with_algorithm(&encrypted, &key, AeadAlgorithm::Aes256GcmSiv);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_calibrate_stays_within_bounds_and_still_derives() {
+        // A tiny target keeps this test fast while still exercising the
+        // search and the resulting parameters against a real derivation.
+        let params = Argon2Params::calibrate(Duration::from_millis(1));
+
+        assert!(params.memory_cost >= CALIBRATION_MIN_MEMORY_COST);
+        assert!(params.memory_cost <= CALIBRATION_MAX_MEMORY_COST);
+        assert_eq!(params.time_cost, Argon2Params::default().time_cost);
+        assert_eq!(params.parallelism, Argon2Params::default().parallelism);
+
+        let key = EncryptionKey::from_password_with_params("test", b"test_salt_16byte", &params).unwrap();
+        assert_eq!(key.as_bytes().len(), 32);
+    }
+
+    #[test]
+    fn test_from_password_with_custom_params() {
+        let password = "custom_params_password";
+        let salt = b"test_salt_16byte";
+        let params = Argon2Params {
+            time_cost: 1,
+            memory_cost: 8192,
+            parallelism: 1,
+        };
+
+        let key1 = EncryptionKey::from_password_with_params(password, salt, &params).unwrap();
+        let key2 = EncryptionKey::from_password_with_params(password, salt, &params).unwrap();
+        assert_eq!(key1.as_bytes(), key2.as_bytes());
+
+        // A different parameter set derives a different key even from the same password/salt
+        let default_key = EncryptionKey::from_password(password, salt).unwrap();
+        assert_ne!(key1.as_bytes(), default_key.as_bytes());
+    }
 }
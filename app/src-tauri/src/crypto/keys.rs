@@ -1,8 +1,10 @@
 // BIP32 key derivation
 
 use std::str::FromStr;
-use bitcoin::bip32::{DerivationPath, Xpriv};
-use bitcoin::secp256k1::Secp256k1;
+use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
+use bitcoin::hashes::{hash160, sha256, Hash};
+use bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use bitcoin::secp256k1::{Message, Secp256k1};
 use bitcoin::Network;
 
 use crate::error::WalletError;
@@ -52,6 +54,84 @@ impl MasterKey {
 
         Ok(derived_key)
     }
+
+    /// Derive the extended public key at `path`, neutered of its private key
+    /// material. This is the watch-only half of an account: safe to hand to
+    /// an online wallet since it can derive addresses but never sign.
+    pub fn account_xpub(&self, path: &DerivationPath) -> Result<Xpub, WalletError> {
+        let secp = Secp256k1::new();
+        let account_key = self.derive_path(path)?;
+        Ok(Xpub::from_priv(&secp, &account_key))
+    }
+
+    /// Derive the account xpub at `path` and prefix it with its BIP380
+    /// `[fingerprint/path]` key origin, e.g.
+    /// `[73c5da0a/84'/0'/0']xpub6C...`.
+    ///
+    /// This is the `KeySource`-prefixed key an output descriptor embeds;
+    /// `wallet::hd::HDWallet::export_account_descriptors` wraps it in the
+    /// script-type function and appends a checksum.
+    pub fn xpub_with_origin(&self, path: &DerivationPath) -> Result<String, WalletError> {
+        let account_xpub = self.account_xpub(path)?;
+        let origin_path = path.to_string();
+        let origin_path = origin_path.trim_start_matches("m/").trim_start_matches('m');
+        Ok(format!("[{}/{}]{}", self.fingerprint(), origin_path, account_xpub))
+    }
+
+    /// Sign `content` with the master private key, producing a 65-byte
+    /// recoverable ECDSA signature (64-byte compact signature || 1-byte
+    /// recovery id) alongside the fingerprint of the signing key.
+    ///
+    /// Recoverability lets `verify_content_signature` check the signature
+    /// against an expected fingerprint with only the content and signature
+    /// in hand — no private key (or the password that unlocks one) needed
+    /// at verify time.
+    pub fn sign_content(&self, content: &[u8]) -> ([u8; 65], [u8; 4]) {
+        let secp = Secp256k1::new();
+        let digest = sha256::Hash::hash(content);
+        let message = Message::from_digest(digest.to_byte_array());
+        let recoverable_sig = secp.sign_ecdsa_recoverable(&message, &self.extended_key.private_key);
+        let (recovery_id, compact) = recoverable_sig.serialize_compact();
+
+        let mut sig = [0u8; 65];
+        sig[..64].copy_from_slice(&compact);
+        sig[64] = recovery_id.to_i32() as u8;
+
+        (sig, self.fingerprint_bytes())
+    }
+}
+
+/// Recover the signer's public key from a `MasterKey::sign_content`
+/// signature and check its fingerprint matches `expected_fingerprint`,
+/// without needing any private key material.
+///
+/// Used by `storage::encrypted::load_wallet_from_path` to detect a
+/// ciphertext substituted from a different wallet before attempting
+/// decryption.
+pub fn verify_content_signature(
+    content: &[u8],
+    sig: &[u8],
+    expected_fingerprint: [u8; 4],
+) -> Result<bool, WalletError> {
+    if sig.len() != 65 {
+        return Err(WalletError::CryptoError("Content signature must be 65 bytes".to_string()));
+    }
+
+    let secp = Secp256k1::new();
+    let digest = sha256::Hash::hash(content);
+    let message = Message::from_digest(digest.to_byte_array());
+
+    let recovery_id = RecoveryId::from_i32(sig[64] as i32)
+        .map_err(|e| WalletError::CryptoError(format!("Invalid recovery id: {}", e)))?;
+    let recoverable_sig = RecoverableSignature::from_compact(&sig[..64], recovery_id)
+        .map_err(|e| WalletError::CryptoError(format!("Invalid recoverable signature: {}", e)))?;
+
+    let recovered_pubkey = secp
+        .recover_ecdsa(&message, &recoverable_sig)
+        .map_err(|e| WalletError::CryptoError(format!("Failed to recover public key: {}", e)))?;
+
+    let fingerprint = hash160::Hash::hash(&recovered_pubkey.serialize());
+    Ok(fingerprint[..4] == expected_fingerprint)
 }
 
 /// Parse derivation path string
@@ -89,6 +169,56 @@ mod tests {
         assert_ne!(child_key.to_priv().to_bytes(), master_key.extended_key.to_priv().to_bytes());
     }
 
+    #[test]
+    fn test_xpub_with_origin_has_fingerprint_and_path_prefix() {
+        let seed = [3u8; 64];
+        let master_key = MasterKey::from_seed(&seed, Network::Bitcoin).unwrap();
+
+        let path = parse_derivation_path("m/84'/0'/0'").unwrap();
+        let with_origin = master_key.xpub_with_origin(&path).unwrap();
+
+        let expected_prefix = format!("[{}/84'/0'/0']", master_key.fingerprint());
+        assert!(with_origin.starts_with(&expected_prefix));
+
+        let account_xpub = master_key.account_xpub(&path).unwrap();
+        assert!(with_origin.ends_with(&account_xpub.to_string()));
+    }
+
+    #[test]
+    fn test_sign_content_verifies_against_own_fingerprint() {
+        let seed = [4u8; 64];
+        let master_key = MasterKey::from_seed(&seed, Network::Bitcoin).unwrap();
+
+        let content = b"salt||nonce||encrypted_data||version";
+        let (sig, fingerprint) = master_key.sign_content(content);
+        assert_eq!(fingerprint, master_key.fingerprint_bytes());
+
+        assert!(verify_content_signature(content, &sig, fingerprint).unwrap());
+    }
+
+    #[test]
+    fn test_verify_content_signature_rejects_tampered_content() {
+        let seed = [5u8; 64];
+        let master_key = MasterKey::from_seed(&seed, Network::Bitcoin).unwrap();
+
+        let (sig, fingerprint) = master_key.sign_content(b"original content");
+
+        assert!(!verify_content_signature(b"substituted content", &sig, fingerprint).unwrap());
+    }
+
+    #[test]
+    fn test_verify_content_signature_rejects_wrong_fingerprint() {
+        let seed = [6u8; 64];
+        let master_key = MasterKey::from_seed(&seed, Network::Bitcoin).unwrap();
+        let other_key = MasterKey::from_seed(&[7u8; 64], Network::Bitcoin).unwrap();
+
+        let content = b"some wallet file contents";
+        let (sig, _) = master_key.sign_content(content);
+
+        // Signed by `master_key`, but checked against a different wallet's fingerprint
+        assert!(!verify_content_signature(content, &sig, other_key.fingerprint_bytes()).unwrap());
+    }
+
     #[test]
     fn test_parse_derivation_path() {
         // Valid paths
@@ -4,6 +4,7 @@ use std::str::FromStr;
 use bip39::{Language, Mnemonic};
 use rand::RngCore;
 
+use crate::crypto::secret::SecretBytes;
 use crate::error::WalletError;
 
 /// Generate a new BIP39 mnemonic
@@ -46,14 +47,27 @@ pub fn get_wordlist() -> Vec<String> {
 }
 
 /// Derive seed from mnemonic and optional passphrase
-pub fn mnemonic_to_seed(mnemonic: &str, passphrase: Option<&str>) -> Result<[u8; 64], WalletError> {
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: Option<&str>) -> Result<SecretBytes<64>, WalletError> {
     let mnemonic = Mnemonic::from_str(mnemonic)
         .map_err(|_| WalletError::InvalidMnemonic)?;
 
     let passphrase = passphrase.unwrap_or("");
     let seed = mnemonic.to_seed(passphrase);
 
-    Ok(seed)
+    Ok(SecretBytes::new(seed))
+}
+
+/// Extract the raw entropy bytes backing a BIP39 mnemonic (for Shamir splitting)
+pub fn mnemonic_to_entropy(mnemonic: &str) -> Result<Vec<u8>, WalletError> {
+    let mnemonic = Mnemonic::from_str(mnemonic).map_err(|_| WalletError::InvalidMnemonic)?;
+    Ok(mnemonic.to_entropy())
+}
+
+/// Rebuild a BIP39 mnemonic from raw entropy bytes (16 bytes = 12 words, 32 bytes = 24 words)
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, WalletError> {
+    let mnemonic = Mnemonic::from_entropy_in(Language::English, entropy)
+        .map_err(|e| WalletError::CryptoError(format!("Invalid entropy: {}", e)))?;
+    Ok(mnemonic.to_string())
 }
 
 #[cfg(test)]
@@ -92,14 +106,24 @@ mod tests {
     fn test_mnemonic_to_seed() {
         let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
         let seed = mnemonic_to_seed(mnemonic, None).unwrap();
-        assert_eq!(seed.len(), 64);
+        assert_eq!(seed.as_bytes().len(), 64);
 
         // Test with passphrase
         let seed_with_pass = mnemonic_to_seed(mnemonic, Some("password")).unwrap();
-        assert_eq!(seed_with_pass.len(), 64);
+        assert_eq!(seed_with_pass.as_bytes().len(), 64);
 
         // Seeds should be different
-        assert_ne!(seed, seed_with_pass);
+        assert_ne!(seed.as_bytes(), seed_with_pass.as_bytes());
+    }
+
+    #[test]
+    fn test_mnemonic_entropy_roundtrip() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let entropy = mnemonic_to_entropy(mnemonic).unwrap();
+        assert_eq!(entropy.len(), 16);
+
+        let rebuilt = entropy_to_mnemonic(&entropy).unwrap();
+        assert_eq!(rebuilt, mnemonic);
     }
 
     #[test]
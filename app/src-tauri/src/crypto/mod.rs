@@ -0,0 +1,8 @@
+// Cryptographic primitives (encryption, key derivation, mnemonic handling)
+
+pub mod ecies;
+pub mod encryption;
+pub mod keys;
+pub mod mnemonic;
+pub mod secret;
+pub mod slip39;
@@ -0,0 +1,95 @@
+// Zeroizing wrappers for secret material (mnemonics, passphrases, seeds)
+//
+// Plain `String`/`[u8; N]` fields get copied by every `.clone()`, linger in
+// memory after the value is dropped, and print their contents through any
+// stray `{:?}`. These wrappers zeroize on drop and redact themselves from
+// `Debug` so the only way to read the contents is the explicit accessor.
+
+use std::fmt;
+use std::ops::Deref;
+use serde::{Deserialize, Serialize};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// A `String` that is wiped from memory on drop and never printed via `Debug`.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for SecretString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+/// A fixed-size secret byte buffer (e.g. a BIP32 seed), wiped from memory on drop.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
+pub struct SecretBytes<const N: usize>([u8; N]);
+
+impl<const N: usize> SecretBytes<N> {
+    pub fn new(value: [u8; N]) -> Self {
+        Self(value)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> fmt::Debug for SecretBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_string_debug_is_redacted() {
+        let secret = SecretString::from("correct horse battery staple");
+        assert_eq!(format!("{:?}", secret), "<redacted>");
+        assert_eq!(secret.as_str(), "correct horse battery staple");
+    }
+
+    #[test]
+    fn test_secret_string_deref_coerces_to_str() {
+        let secret = SecretString::from("abandon abandon abandon".to_string());
+        fn takes_str(s: &str) -> usize {
+            s.len()
+        }
+        assert_eq!(takes_str(&secret), "abandon abandon abandon".len());
+    }
+
+    #[test]
+    fn test_secret_bytes_debug_is_redacted() {
+        let secret = SecretBytes::new([0x42u8; 64]);
+        assert_eq!(format!("{:?}", secret), "<redacted>");
+        assert_eq!(secret.as_bytes(), &[0x42u8; 64]);
+    }
+}
@@ -10,13 +10,33 @@ mod state;
 mod error;
 mod types;
 
+use tauri::{Emitter, Manager};
+
 use state::WalletState;
 
+/// How often the idle-lock background task checks `last_activity` against
+/// the wallet's configured timeout
+const IDLE_CHECK_INTERVAL_SECS: u64 = 5;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .manage(WalletState::new())
+        .setup(|app| {
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(IDLE_CHECK_INTERVAL_SECS)).await;
+
+                    let locked_due_to_idle = app_handle.state::<WalletState>().check_idle_timeout();
+                    if locked_due_to_idle {
+                        let _ = app_handle.emit("wallet-locked", ());
+                    }
+                }
+            });
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Wallet management
             commands::create_wallet,
@@ -26,27 +46,48 @@ pub fn run() {
             commands::wallet_exists,
             commands::get_wallet_info,
             commands::export_mnemonic,
+            commands::reveal_mnemonic,
             commands::validate_mnemonic,
             commands::get_bip39_wordlist,
             commands::generate_mnemonic_cmd,
             commands::delete_wallet,
             commands::change_password,
+            commands::export_shamir_shares,
+            commands::import_shamir_shares,
+            commands::export_wallet_backup,
+            commands::import_wallet_backup,
+            commands::export_wallet_sealed,
+            commands::import_wallet_sealed,
+            commands::set_active_descriptor,
 
             // Address operations
             commands::derive_address,
             commands::derive_custom_address,
             commands::derive_addresses,
+            commands::derive_account,
             commands::validate_derivation_path,
+            commands::export_descriptor,
+            commands::account_descriptor,
+            commands::derive_from_descriptor,
+            commands::verify_receive_address,
 
             // Transaction operations
             commands::parse_psbt,
             commands::sign_psbt,
             commands::get_transaction_details,
+            commands::sign_message,
+            commands::verify_message,
 
             // QR utilities
             commands::generate_qr,
             commands::generate_qr_compressed,
             commands::decode_compressed_qr,
+            commands::generate_ur_parts,
+            commands::generate_more_ur_parts,
+            commands::decode_ur_parts,
+            commands::generate_qr_animated,
+            commands::generate_more_qr_animated_frames,
+            commands::decode_qr_animated,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -6,10 +6,17 @@ use zeroize::{Zeroize, ZeroizeOnDrop};
 use bitcoin::bip32::Xpriv;
 use bitcoin::Network;
 
+use crate::crypto::secret::SecretString;
+
+/// Auto-lock after this many seconds of inactivity unless `unlock` is given
+/// an explicit timeout
+pub const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 300;
+
 /// Global wallet state
 pub struct WalletState {
     pub unlocked: Mutex<Option<UnlockedWallet>>,
     pub last_activity: Mutex<DateTime<Utc>>,
+    idle_timeout_secs: Mutex<u64>,
 }
 
 impl WalletState {
@@ -17,19 +24,68 @@ impl WalletState {
         Self {
             unlocked: Mutex::new(None),
             last_activity: Mutex::new(Utc::now()),
+            idle_timeout_secs: Mutex::new(DEFAULT_IDLE_TIMEOUT_SECS),
         }
     }
 
-    /// Unlock wallet and store in memory
-    pub fn unlock(&self, mnemonic: String, network: Network, fingerprint: String) {
+    /// Unlock wallet and store in memory. `timeout_secs` configures how long
+    /// the wallet may sit idle before `check_idle_timeout` auto-locks it;
+    /// defaults to `DEFAULT_IDLE_TIMEOUT_SECS` if not given.
+    pub fn unlock(
+        &self,
+        mnemonic: impl Into<SecretString>,
+        network: Network,
+        fingerprint: String,
+        timeout_secs: Option<u64>,
+    ) {
         if let Ok(mut unlocked) = self.unlocked.lock() {
             *unlocked = Some(UnlockedWallet {
-                mnemonic,
+                mnemonic: mnemonic.into(),
                 master_key: None, // Could derive later if needed
                 network,
                 fingerprint,
+                active_descriptor: None,
             });
         }
+        if let Ok(mut timeout) = self.idle_timeout_secs.lock() {
+            *timeout = timeout_secs.unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS);
+        }
+        self.update_activity();
+    }
+
+    /// If the wallet is unlocked and has been idle longer than its configured
+    /// timeout, lock it (so `UnlockedWallet`'s `ZeroizeOnDrop` wipes the
+    /// mnemonic) and report that it did so. Meant to be polled periodically.
+    pub fn check_idle_timeout(&self) -> bool {
+        if !self.is_unlocked() {
+            return false;
+        }
+
+        let timeout_secs = self.idle_timeout_secs.lock().map(|t| *t).unwrap_or(DEFAULT_IDLE_TIMEOUT_SECS);
+        let idle_for = self
+            .last_activity
+            .lock()
+            .map(|last| Utc::now().signed_duration_since(*last))
+            .unwrap_or_default();
+
+        if idle_for.num_seconds() >= timeout_secs as i64 {
+            self.lock();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Configure the output descriptor (receive, change) the wallet is tracked
+    /// under, e.g. so multisig or other non-default script paths can be
+    /// derived and matched against instead of only the implicit BIP44/49/84/86
+    /// paths. No-op if the wallet is locked.
+    pub fn set_active_descriptor(&self, receive: String, change: String) {
+        if let Ok(mut unlocked) = self.unlocked.lock() {
+            if let Some(wallet) = unlocked.as_mut() {
+                wallet.active_descriptor = Some((receive, change));
+            }
+        }
     }
 
     /// Get unlocked wallet data
@@ -68,12 +124,16 @@ impl WalletState {
 /// Unlocked wallet data (stored in memory)
 #[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct UnlockedWallet {
-    pub mnemonic: String,
+    pub mnemonic: SecretString,
     #[zeroize(skip)]
     pub master_key: Option<Xpriv>,
     #[zeroize(skip)]
     pub network: Network,
     pub fingerprint: String,
+    /// The (receive, change) output descriptor pair the wallet is currently
+    /// configured from, if any — see `WalletState::set_active_descriptor`
+    #[zeroize(skip)]
+    pub active_descriptor: Option<(String, String)>,
 }
 
 impl Default for WalletState {
@@ -0,0 +1,278 @@
+// Versioned remote backup store
+
+use std::collections::HashMap;
+use std::fs;
+
+use crate::error::WalletError;
+use crate::storage::encrypted::{get_wallet_path, load_wallet_from_path, load_wallet_from_path_expecting};
+
+/// A remote store for encrypted wallet backups, keyed by an opaque string
+/// and guarded by a monotonically increasing version per key.
+///
+/// Implementations never see plaintext: `push_wallet_backup`/
+/// `pull_wallet_backup` hand them the ciphertext bytes already produced by
+/// `encode_wallet_file`/`save_wallet` — encryption and decryption stay
+/// entirely client-side.
+pub trait BackupStore {
+    /// Store `blob` under `key` at `version`. Rejects the write (without
+    /// touching the stored value) if the store already holds a version
+    /// greater than `version` for that key, so a stale device can't clobber
+    /// a newer backup pushed from elsewhere.
+    fn put(&mut self, key: &str, blob: &[u8], version: u64) -> Result<(), WalletError>;
+
+    /// Fetch the blob currently stored under `key`, along with its version.
+    fn get(&self, key: &str) -> Result<(Vec<u8>, u64), WalletError>;
+}
+
+/// In-memory `BackupStore`, used for tests and as a reference for a real
+/// network-backed implementation (e.g. an HTTP client against a VSS-style
+/// endpoint).
+#[derive(Default)]
+pub struct InMemoryBackupStore {
+    entries: HashMap<String, (Vec<u8>, u64)>,
+}
+
+impl InMemoryBackupStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl BackupStore for InMemoryBackupStore {
+    fn put(&mut self, key: &str, blob: &[u8], version: u64) -> Result<(), WalletError> {
+        if let Some((_, stored_version)) = self.entries.get(key) {
+            if *stored_version > version {
+                return Err(WalletError::StorageError(format!(
+                    "Refusing to overwrite newer backup for '{}': stored version {} > {}",
+                    key, stored_version, version
+                )));
+            }
+        }
+
+        self.entries.insert(key.to_string(), (blob.to_vec(), version));
+        Ok(())
+    }
+
+    fn get(&self, key: &str) -> Result<(Vec<u8>, u64), WalletError> {
+        self.entries
+            .get(key)
+            .cloned()
+            .ok_or_else(|| WalletError::StorageError(format!("No backup found for '{}'", key)))
+    }
+}
+
+/// Push the local encrypted wallet file to `store` under `key` at `version`,
+/// rejecting the push if `store` already holds a newer version.
+pub fn push_wallet_backup(
+    store: &mut dyn BackupStore,
+    key: &str,
+    version: u64,
+) -> Result<(), WalletError> {
+    let wallet_path = get_wallet_path()?;
+    let file_bytes = fs::read(&wallet_path)
+        .map_err(|e| WalletError::StorageError(format!("Failed to read wallet file: {}", e)))?;
+
+    store.put(key, &file_bytes, version)
+}
+
+/// Pull the backup stored under `key` in `store` into the default wallet
+/// location, returning its version.
+///
+/// Mirrors `import_wallet`'s safety property: the blob is validated as a
+/// decryptable wallet file under `password` before anything is written, and
+/// an existing local wallet is left untouched unless `overwrite` is set. If
+/// `expected_fingerprint` is given (the fingerprint of the wallet this key
+/// is supposed to hold, known out-of-band), the pulled file must also carry
+/// a content signature recovering to it, so a remote store serving back a
+/// different wallet's complete file — not just a spliced one — is rejected
+/// before it ever reaches the local wallet path.
+pub fn pull_wallet_backup(
+    store: &dyn BackupStore,
+    key: &str,
+    password: &str,
+    overwrite: bool,
+    expected_fingerprint: Option<[u8; 4]>,
+) -> Result<u64, WalletError> {
+    let wallet_path = get_wallet_path()?;
+    if wallet_path.exists() && !overwrite {
+        return Err(WalletError::WalletExists);
+    }
+
+    let (file_bytes, version) = store.get(key)?;
+
+    let tmp_path = wallet_path.with_extension("enc.remote-tmp");
+    fs::write(&tmp_path, &file_bytes)
+        .map_err(|e| WalletError::StorageError(format!("Failed to write temp backup file: {}", e)))?;
+
+    let load_result = match expected_fingerprint {
+        Some(fingerprint) => load_wallet_from_path_expecting(&tmp_path, password, fingerprint),
+        None => load_wallet_from_path(&tmp_path, password),
+    };
+    if let Err(e) = load_result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    fs::rename(&tmp_path, &wallet_path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        WalletError::StorageError(format!("Failed to replace wallet file: {}", e))
+    })?;
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::encrypted::{delete_wallet, load_wallet, save_wallet, wallet_exists};
+    use crate::storage::wallet_file::WalletPayload;
+    use bitcoin::Network;
+    use chrono::Utc;
+
+    fn cleanup_test_wallet() {
+        let _ = delete_wallet();
+    }
+
+    #[test]
+    fn test_in_memory_store_put_get_roundtrip() {
+        let mut store = InMemoryBackupStore::new();
+        store.put("wallet-a", b"ciphertext", 1).unwrap();
+
+        let (blob, version) = store.get("wallet-a").unwrap();
+        assert_eq!(blob, b"ciphertext");
+        assert_eq!(version, 1);
+    }
+
+    #[test]
+    fn test_in_memory_store_rejects_stale_put() {
+        let mut store = InMemoryBackupStore::new();
+        store.put("wallet-a", b"v2", 2).unwrap();
+
+        let result = store.put("wallet-a", b"v1", 1);
+        assert!(result.is_err());
+
+        // Stored value is unchanged by the rejected write
+        let (blob, version) = store.get("wallet-a").unwrap();
+        assert_eq!(blob, b"v2");
+        assert_eq!(version, 2);
+    }
+
+    #[test]
+    fn test_in_memory_store_get_missing_key() {
+        let store = InMemoryBackupStore::new();
+        assert!(store.get("missing").is_err());
+    }
+
+    #[test]
+    fn test_push_and_pull_wallet_backup_roundtrip() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "backup store roundtrip test".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "b0b0b0b0".to_string(),
+            created_at: Utc::now(),
+        };
+        let password = "backup_store_password";
+        save_wallet(&payload, password).unwrap();
+
+        let mut store = InMemoryBackupStore::new();
+        push_wallet_backup(&mut store, "device-a", 1).unwrap();
+
+        cleanup_test_wallet();
+        assert!(!wallet_exists());
+
+        let version = pull_wallet_backup(&store, "device-a", password, false, None).unwrap();
+        assert_eq!(version, 1);
+
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_pull_wallet_backup_rejects_wrong_password() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "wrong password pull test".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "c0c0c0c0".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&payload, "right_password").unwrap();
+
+        let mut store = InMemoryBackupStore::new();
+        push_wallet_backup(&mut store, "device-a", 1).unwrap();
+        cleanup_test_wallet();
+
+        let result = pull_wallet_backup(&store, "device-a", "wrong_password", false, None);
+        assert!(result.is_err());
+        assert!(!wallet_exists());
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_pull_wallet_backup_refuses_existing_without_overwrite() {
+        cleanup_test_wallet();
+
+        let remote_payload = WalletPayload {
+            mnemonic: "remote backup".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "d0d0d0d0".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&remote_payload, "remote_password").unwrap();
+
+        let mut store = InMemoryBackupStore::new();
+        push_wallet_backup(&mut store, "device-a", 1).unwrap();
+
+        let local_payload = WalletPayload {
+            mnemonic: "local wallet stays".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "e0e0e0e0".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&local_payload, "local_password").unwrap();
+
+        let result = pull_wallet_backup(&store, "device-a", "remote_password", false, None);
+        assert!(matches!(result, Err(WalletError::WalletExists)));
+
+        let loaded = load_wallet("local_password").unwrap();
+        assert_eq!(loaded.mnemonic, local_payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_push_wallet_backup_version_guard() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "version guard test".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "f0f0f0f0".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&payload, "version_guard_password").unwrap();
+
+        let mut store = InMemoryBackupStore::new();
+        push_wallet_backup(&mut store, "device-a", 5).unwrap();
+
+        // A push with an older version is rejected
+        let result = push_wallet_backup(&mut store, "device-a", 3);
+        assert!(result.is_err());
+
+        let (_, stored_version) = store.get("device-a").unwrap();
+        assert_eq!(stored_version, 5);
+
+        cleanup_test_wallet();
+    }
+}
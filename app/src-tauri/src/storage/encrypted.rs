@@ -4,9 +4,35 @@ use std::path::PathBuf;
 use std::fs;
 use aes_gcm::aead::rand_core::RngCore;
 use aes_gcm::aead::OsRng;
+use serde::{Deserialize, Serialize};
 use crate::error::WalletError;
-use crate::storage::wallet_file::{WalletFile, WalletPayload};
-use crate::crypto::encryption::{EncryptionKey, encrypt, decrypt};
+use crate::storage::wallet_file::{WalletFile, WalletFileV1, WalletFileV2, WalletPayload};
+use crate::crypto::encryption::{
+    AeadAlgorithm, Argon2Params, EncryptionKey, decrypt_with_algorithm, encrypt_with_algorithm,
+};
+use crate::crypto::ecies::{ecies_decrypt, ecies_encrypt};
+use crate::crypto::keys::{verify_content_signature, MasterKey};
+use crate::crypto::mnemonic::mnemonic_to_seed;
+use bitcoin::secp256k1::{PublicKey, SecretKey};
+
+/// Just enough of the on-disk header to tell which `WalletFile` layout to
+/// decode the rest of the bytes with.
+#[derive(Serialize, Deserialize)]
+struct FileVersionHeader {
+    version: u32,
+}
+
+/// The bytes a `WalletFile`'s `content_sig` is signed over: everything that
+/// identifies this specific ciphertext, so a validly-encrypted file swapped
+/// in from another wallet changes the signed content and fails verification.
+fn signed_content(salt: &[u8], nonce: &[u8], encrypted_data: &[u8], version: u32) -> Vec<u8> {
+    let mut content = Vec::with_capacity(salt.len() + nonce.len() + encrypted_data.len() + 4);
+    content.extend_from_slice(salt);
+    content.extend_from_slice(nonce);
+    content.extend_from_slice(encrypted_data);
+    content.extend_from_slice(&version.to_le_bytes());
+    content
+}
 
 /// Get wallet file path
 pub fn get_wallet_path() -> Result<PathBuf, WalletError> {
@@ -41,38 +67,88 @@ pub fn delete_wallet() -> Result<(), WalletError> {
     Ok(())
 }
 
-/// Save encrypted wallet file
-pub fn save_wallet(payload: &WalletPayload, password: &str) -> Result<(), WalletError> {
+/// Encrypt a payload under a freshly generated salt/nonce and the given
+/// KDF/AEAD parameter set, and serialize it into the on-disk `WalletFile`
+/// format, without touching the filesystem. The chosen parameters are tagged
+/// into the file itself so `load_wallet` knows how to reverse them later
+/// even if `Argon2Params::default()`/`AeadAlgorithm::default_for_new_wallets()`
+/// change in a future release.
+fn encode_wallet_file(
+    payload: &WalletPayload,
+    password: &str,
+    kdf: &Argon2Params,
+    aead: AeadAlgorithm,
+) -> Result<Vec<u8>, WalletError> {
     // Generate random salt for Argon2
     let mut salt = [0u8; 32];
     OsRng.fill_bytes(&mut salt);
 
     // Derive encryption key from password
-    let key = EncryptionKey::from_password(password, &salt)?;
+    let key = EncryptionKey::from_password_with_params(password, &salt, kdf)?;
 
     // Serialize payload to bytes
     let payload_bytes = bincode::serde::encode_to_vec(payload, bincode::config::standard())
         .map_err(|e| WalletError::SerializationError(format!("Failed to serialize payload: {}", e)))?;
 
     // Encrypt the payload
-    let encrypted = encrypt(&payload_bytes, &key)?;
+    let encrypted = encrypt_with_algorithm(&payload_bytes, &key, aead)?;
+
+    // Extract nonce and ciphertext, with lengths dictated by the chosen algorithm
+    let nonce_len = aead.nonce_len();
+    let tag_len = aead.tag_len();
+    let nonce = encrypted[..nonce_len].to_vec();
+    let ciphertext_with_tag = encrypted[nonce_len..].to_vec();
+
+    let encrypted_data = ciphertext_with_tag[..ciphertext_with_tag.len() - tag_len].to_vec();
+    let auth_tag = ciphertext_with_tag[ciphertext_with_tag.len() - tag_len..].to_vec();
 
-    // Extract nonce and ciphertext
-    let nonce = encrypted[..12].to_vec();
-    let ciphertext_with_tag = encrypted[12..].to_vec();
+    // Sign the file's own content with the wallet's master key, so a
+    // ciphertext substituted from a different wallet is caught on load
+    // before decryption is even attempted. Re-derived here (rather than
+    // threaded in as a parameter) so save_wallet/change_password callers
+    // don't need to change.
+    let seed = mnemonic_to_seed(&payload.mnemonic, payload.passphrase.as_deref())?;
+    let master_key = MasterKey::from_seed(seed.as_bytes(), payload.network)?;
+    let content = signed_content(&salt, &nonce, &encrypted_data, WalletFile::VERSION);
+    let (content_sig, signer_fingerprint) = master_key.sign_content(&content);
 
     // Create wallet file structure
     let wallet_file = WalletFile {
         version: WalletFile::VERSION,
+        kdf: kdf.clone(),
+        aead,
         salt: salt.to_vec(),
         nonce,
-        encrypted_data: ciphertext_with_tag[..ciphertext_with_tag.len() - 16].to_vec(),
-        auth_tag: ciphertext_with_tag[ciphertext_with_tag.len() - 16..].to_vec(),
+        encrypted_data,
+        auth_tag,
+        content_sig: content_sig.to_vec(),
+        signer_fingerprint,
     };
 
     // Serialize wallet file
-    let file_bytes = bincode::serde::encode_to_vec(&wallet_file, bincode::config::standard())
-        .map_err(|e| WalletError::SerializationError(format!("Failed to serialize wallet file: {}", e)))?;
+    bincode::serde::encode_to_vec(&wallet_file, bincode::config::standard())
+        .map_err(|e| WalletError::SerializationError(format!("Failed to serialize wallet file: {}", e)))
+}
+
+/// Save encrypted wallet file under the default KDF/AEAD parameter set
+pub fn save_wallet(payload: &WalletPayload, password: &str) -> Result<(), WalletError> {
+    save_wallet_with_params(
+        payload,
+        password,
+        &Argon2Params::default(),
+        AeadAlgorithm::default_for_new_wallets(),
+    )
+}
+
+/// Save encrypted wallet file under an explicit KDF/AEAD parameter set, e.g.
+/// to raise the Argon2 cost or switch to AES-256-GCM-SIV going forward
+pub fn save_wallet_with_params(
+    payload: &WalletPayload,
+    password: &str,
+    kdf: &Argon2Params,
+    aead: AeadAlgorithm,
+) -> Result<(), WalletError> {
+    let file_bytes = encode_wallet_file(payload, password, kdf, aead)?;
 
     // Write to disk
     let wallet_path = get_wallet_path()?;
@@ -82,46 +158,280 @@ pub fn save_wallet(payload: &WalletPayload, password: &str) -> Result<(), Wallet
     Ok(())
 }
 
-/// Load encrypted wallet file
-pub fn load_wallet(password: &str) -> Result<WalletPayload, WalletError> {
-    // Read wallet file from disk
+/// Save encrypted wallet file under Argon2id parameters calibrated to take
+/// roughly `target_derivation_time` on the current hardware, e.g. for users
+/// who want a stronger-than-default KDF cost. The chosen parameters are
+/// tagged into the file like any other, so `load_wallet` reverses them
+/// without needing to recalibrate.
+pub fn save_wallet_calibrated(
+    payload: &WalletPayload,
+    password: &str,
+    target_derivation_time: std::time::Duration,
+) -> Result<(), WalletError> {
+    let kdf = Argon2Params::calibrate(target_derivation_time);
+    save_wallet_with_params(payload, password, &kdf, AeadAlgorithm::default_for_new_wallets())
+}
+
+/// Re-encrypt the wallet file under a new password, leaving the original file
+/// untouched if anything goes wrong.
+///
+/// Loads and decrypts the existing wallet with `old_password` (failing with
+/// `WalletError::InvalidPassword` if that doesn't work), then writes the
+/// re-encrypted payload to a temp file next to `wallet.enc` and atomically
+/// renames it into place, so a crash or error midway never leaves a
+/// half-written or undecryptable wallet file on disk.
+///
+/// Re-encrypts under the file's own KDF/AEAD parameters rather than resetting
+/// to defaults, so a wallet calibrated via `save_wallet_calibrated` (or saved
+/// under GCM-SIV) keeps that choice across a password change.
+pub fn change_password(old_password: &str, new_password: &str) -> Result<(), WalletError> {
     let wallet_path = get_wallet_path()?;
+    let (payload, kdf, aead) = load_wallet_from_path_impl(&wallet_path, old_password, None)
+        .map_err(|_| WalletError::InvalidPassword)?;
+
+    let file_bytes = encode_wallet_file(&payload, new_password, &kdf, aead)?;
+
+    let tmp_path = wallet_path.with_extension("enc.tmp");
+
+    fs::write(&tmp_path, &file_bytes)
+        .map_err(|e| WalletError::StorageError(format!("Failed to write temp wallet file: {}", e)))?;
+
+    fs::rename(&tmp_path, &wallet_path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        WalletError::StorageError(format!("Failed to replace wallet file: {}", e))
+    })?;
+
+    Ok(())
+}
+
+/// Load encrypted wallet file, honoring whatever KDF/AEAD parameters it was
+/// written under — including files from before those parameters were tagged
+/// (format version `WalletFile::VERSION_LEGACY_UNTAGGED`), which are read
+/// under the implicit defaults they were originally saved with. Such files
+/// are migrated to the current tagged format the next time they're saved
+/// (e.g. via `change_password`), not on load.
+///
+/// Only checks the content signature against the file's own stored
+/// `signer_fingerprint`, so this alone catches a splice (another wallet's
+/// ciphertext under this file's signature) but not a complete foreign file
+/// (another wallet's ciphertext, signature, and fingerprint together) —
+/// there's no independently-known fingerprint to hold the default wallet
+/// path to. Callers who know in advance which wallet they expect (importing
+/// a named backup, pulling from a remote store) should use
+/// `load_wallet_from_path_expecting` instead.
+pub fn load_wallet(password: &str) -> Result<WalletPayload, WalletError> {
+    load_wallet_from_path(&get_wallet_path()?, password)
+}
+
+/// Same as `load_wallet`, but against an arbitrary file instead of the
+/// default wallet path.
+pub(crate) fn load_wallet_from_path(wallet_path: &PathBuf, password: &str) -> Result<WalletPayload, WalletError> {
+    load_wallet_from_path_impl(wallet_path, password, None).map(|(payload, _, _)| payload)
+}
+
+/// Same as `load_wallet_from_path`, but also rejects the file unless its
+/// content signature recovers to `expected_fingerprint` — the fingerprint of
+/// the wallet the caller actually intends to load, known out-of-band (e.g.
+/// from a prior watch-only pairing or a previously exported xpub). This is
+/// what closes the substitution gap `load_wallet_from_path` can't: a
+/// complete foreign file (ciphertext, signature, and fingerprint all from a
+/// different wallet) still fails here, because its signature never recovers
+/// to the fingerprint the caller expected.
+pub(crate) fn load_wallet_from_path_expecting(
+    wallet_path: &PathBuf,
+    password: &str,
+    expected_fingerprint: [u8; 4],
+) -> Result<WalletPayload, WalletError> {
+    load_wallet_from_path_impl(wallet_path, password, Some(expected_fingerprint)).map(|(payload, _, _)| payload)
+}
+
+/// Same as `load_wallet_from_path_impl`, but also returns the KDF/AEAD
+/// parameters the file was actually encrypted under, so a caller that's
+/// about to re-encrypt (e.g. `change_password`) can keep them instead of
+/// silently resetting to defaults.
+fn load_wallet_from_path_impl(
+    wallet_path: &PathBuf,
+    password: &str,
+    expected_fingerprint: Option<[u8; 4]>,
+) -> Result<(WalletPayload, Argon2Params, AeadAlgorithm), WalletError> {
     if !wallet_path.exists() {
         return Err(WalletError::StorageError("Wallet file not found".to_string()));
     }
 
-    let file_bytes = fs::read(&wallet_path)
+    let file_bytes = fs::read(wallet_path)
         .map_err(|e| WalletError::StorageError(format!("Failed to read wallet file: {}", e)))?;
 
-    // Deserialize wallet file
-    let (wallet_file, _): (WalletFile, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
+    // Peek the version field only, so we know which full layout to decode with
+    let (header, _): (FileVersionHeader, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
         .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize wallet file: {}", e)))?;
 
-    // Verify version
-    if wallet_file.version != WalletFile::VERSION {
+    let (kdf, aead, salt, nonce, encrypted_data, auth_tag) = if header.version == WalletFile::VERSION_LEGACY_UNTAGGED {
+        let (legacy, _): (WalletFileV1, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
+            .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize wallet file: {}", e)))?;
+
+        (Argon2Params::default(), AeadAlgorithm::Aes256Gcm, legacy.salt, legacy.nonce, legacy.encrypted_data, legacy.auth_tag)
+    } else if header.version == WalletFile::VERSION_UNSIGNED {
+        let (unsigned, _): (WalletFileV2, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
+            .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize wallet file: {}", e)))?;
+
+        (unsigned.kdf, unsigned.aead, unsigned.salt, unsigned.nonce, unsigned.encrypted_data, unsigned.auth_tag)
+    } else if header.version == WalletFile::VERSION {
+        let (wallet_file, _): (WalletFile, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
+            .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize wallet file: {}", e)))?;
+
+        // Verify the file's content signature before attempting decryption,
+        // so a validly-encrypted ciphertext substituted from a different
+        // wallet is rejected up front instead of silently decrypting into
+        // the wrong payload. Without an externally-known expectation, the
+        // only fingerprint available to check against is the one stored in
+        // the file itself — sufficient to catch a splice, not a complete
+        // foreign file.
+        let fingerprint_to_check = expected_fingerprint.unwrap_or(wallet_file.signer_fingerprint);
+        let content = signed_content(&wallet_file.salt, &wallet_file.nonce, &wallet_file.encrypted_data, wallet_file.version);
+        let verified = verify_content_signature(&content, &wallet_file.content_sig, fingerprint_to_check)?;
+        if !verified {
+            return Err(WalletError::StorageError(
+                "Wallet file content signature does not match its claimed signer".to_string(),
+            ));
+        }
+
+        (wallet_file.kdf, wallet_file.aead, wallet_file.salt, wallet_file.nonce, wallet_file.encrypted_data, wallet_file.auth_tag)
+    } else {
         return Err(WalletError::StorageError(format!(
-            "Unsupported wallet version: {} (expected {})",
-            wallet_file.version,
-            WalletFile::VERSION
+            "Unsupported wallet version: {} (expected {}, {}, or {})",
+            header.version,
+            WalletFile::VERSION,
+            WalletFile::VERSION_UNSIGNED,
+            WalletFile::VERSION_LEGACY_UNTAGGED,
         )));
-    }
+    };
 
     // Derive encryption key from password and stored salt
-    let key = EncryptionKey::from_password(password, &wallet_file.salt)?;
+    let key = EncryptionKey::from_password_with_params(password, &salt, &kdf)?;
 
     // Reconstruct encrypted data (nonce + ciphertext + auth_tag)
-    let mut encrypted = Vec::with_capacity(12 + wallet_file.encrypted_data.len() + 16);
-    encrypted.extend_from_slice(&wallet_file.nonce);
-    encrypted.extend_from_slice(&wallet_file.encrypted_data);
-    encrypted.extend_from_slice(&wallet_file.auth_tag);
+    let mut encrypted = Vec::with_capacity(nonce.len() + encrypted_data.len() + auth_tag.len());
+    encrypted.extend_from_slice(&nonce);
+    encrypted.extend_from_slice(&encrypted_data);
+    encrypted.extend_from_slice(&auth_tag);
 
     // Decrypt the payload
-    let payload_bytes = decrypt(&encrypted, &key)?;
+    let payload_bytes = decrypt_with_algorithm(&encrypted, &key, aead)?;
 
     // Deserialize payload
     let (payload, _): (WalletPayload, usize) = bincode::serde::decode_from_slice(&payload_bytes, bincode::config::standard())
         .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize payload: {}", e)))?;
 
+    Ok((payload, kdf, aead))
+}
+
+/// Copy the local encrypted wallet file out to `dest`, e.g. a USB drive,
+/// without re-encrypting or otherwise altering its on-disk format.
+/// `password` is only used to confirm the local wallet actually decrypts
+/// before it's copied anywhere.
+pub fn export_wallet(dest: PathBuf, password: &str) -> Result<(), WalletError> {
+    load_wallet(password)?;
+
+    let wallet_path = get_wallet_path()?;
+    let file_bytes = fs::read(&wallet_path)
+        .map_err(|e| WalletError::StorageError(format!("Failed to read wallet file: {}", e)))?;
+
+    fs::write(&dest, &file_bytes)
+        .map_err(|e| WalletError::StorageError(format!("Failed to write wallet backup: {}", e)))?;
+
+    Ok(())
+}
+
+/// Import an encrypted wallet backup from `src` — e.g. one written by
+/// `export_wallet` from another machine — into the default wallet location.
+///
+/// Validates that `src` decodes to a known `WalletFile` version and
+/// decrypts under `password` before anything is written, so a corrupt or
+/// foreign file never clobbers the local wallet. Refuses to overwrite an
+/// existing local wallet unless `overwrite` is set, returning
+/// `WalletError::WalletExists`.
+///
+/// If `expected_fingerprint` is given (e.g. pinned from a prior watch-only
+/// pairing with this wallet), a version-3 backup must also carry a content
+/// signature that recovers to it — rejecting not just a spliced ciphertext
+/// but a complete file substituted from a different wallet entirely.
+pub fn import_wallet(
+    src: PathBuf,
+    password: &str,
+    overwrite: bool,
+    expected_fingerprint: Option<[u8; 4]>,
+) -> Result<(), WalletError> {
+    let wallet_path = get_wallet_path()?;
+    if wallet_path.exists() && !overwrite {
+        return Err(WalletError::WalletExists);
+    }
+
+    let file_bytes = fs::read(&src)
+        .map_err(|e| WalletError::StorageError(format!("Failed to read wallet backup: {}", e)))?;
+
+    let (header, _): (FileVersionHeader, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard())
+        .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize wallet backup: {}", e)))?;
+
+    if header.version != WalletFile::VERSION
+        && header.version != WalletFile::VERSION_UNSIGNED
+        && header.version != WalletFile::VERSION_LEGACY_UNTAGGED
+    {
+        return Err(WalletError::StorageError(format!(
+            "Unsupported wallet version: {} (expected {}, {}, or {})",
+            header.version,
+            WalletFile::VERSION,
+            WalletFile::VERSION_UNSIGNED,
+            WalletFile::VERSION_LEGACY_UNTAGGED,
+        )));
+    }
+
+    // Confirm the backup actually decrypts under `password` (and, if given,
+    // belongs to the expected wallet) before it's allowed to overwrite anything
+    match expected_fingerprint {
+        Some(fingerprint) => load_wallet_from_path_expecting(&src, password, fingerprint)?,
+        None => load_wallet_from_path(&src, password)?,
+    };
+
+    fs::write(&wallet_path, &file_bytes)
+        .map_err(|e| WalletError::StorageError(format!("Failed to write wallet file: {}", e)))?;
+
+    Ok(())
+}
+
+/// Seal the local wallet's payload to `recipient_pubkey` and write it to
+/// `dest`, e.g. for an inheritance backup or pairing a second device that
+/// holds only a keypair and not the wallet's password. Unlike
+/// `export_wallet`, the result isn't a `WalletFile` at all — there's no
+/// password involved on the recipient's side — just the raw ECIES-sealed
+/// `WalletPayload` bytes; only the holder of `recipient_pubkey`'s matching
+/// private key can recover it with `import_wallet_sealed`.
+pub fn export_wallet_sealed(dest: PathBuf, password: &str, recipient_pubkey: &PublicKey) -> Result<(), WalletError> {
+    let payload = load_wallet(password)?;
+
+    let payload_bytes = bincode::serde::encode_to_vec(&payload, bincode::config::standard())
+        .map_err(|e| WalletError::SerializationError(format!("Failed to serialize payload: {}", e)))?;
+
+    let sealed = ecies_encrypt(&payload_bytes, recipient_pubkey)?;
+
+    fs::write(&dest, &sealed)
+        .map_err(|e| WalletError::StorageError(format!("Failed to write sealed backup: {}", e)))?;
+
+    Ok(())
+}
+
+/// Recover a `WalletPayload` sealed by `export_wallet_sealed`, using the
+/// recipient's private key. The caller is responsible for what happens next
+/// — typically `save_wallet`ing it under a password of the recipient's own
+/// choosing, since the sealed backup carries no password of its own.
+pub fn import_wallet_sealed(src: PathBuf, recipient_secret: &SecretKey) -> Result<WalletPayload, WalletError> {
+    let sealed_bytes = fs::read(&src)
+        .map_err(|e| WalletError::StorageError(format!("Failed to read sealed backup: {}", e)))?;
+
+    let payload_bytes = ecies_decrypt(&sealed_bytes, recipient_secret)?;
+
+    let (payload, _): (WalletPayload, usize) = bincode::serde::decode_from_slice(&payload_bytes, bincode::config::standard())
+        .map_err(|e| WalletError::SerializationError(format!("Failed to deserialize sealed payload: {}", e)))?;
+
     Ok(payload)
 }
 
@@ -157,7 +467,7 @@ mod tests {
 
         // Create test payload
         let payload = WalletPayload {
-            mnemonic: "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string(),
+            mnemonic: "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about".to_string().into(),
             passphrase: None,
             network: Network::Testnet,
             fingerprint: "73c5da0a".to_string(),
@@ -187,7 +497,7 @@ mod tests {
         cleanup_test_wallet();
 
         let payload = WalletPayload {
-            mnemonic: "test mnemonic phrase here".to_string(),
+            mnemonic: "test mnemonic phrase here".to_string().into(),
             passphrase: None,
             network: Network::Bitcoin,
             fingerprint: "12345678".to_string(),
@@ -221,8 +531,8 @@ mod tests {
         cleanup_test_wallet();
 
         let payload = WalletPayload {
-            mnemonic: "test mnemonic with passphrase".to_string(),
-            passphrase: Some("my_secret_passphrase".to_string()),
+            mnemonic: "test mnemonic with passphrase".to_string().into(),
+            passphrase: Some("my_secret_passphrase".to_string().into()),
             network: Network::Bitcoin,
             fingerprint: "abcdef12".to_string(),
             created_at: Utc::now(),
@@ -235,7 +545,7 @@ mod tests {
         let loaded = load_wallet(password).unwrap();
 
         // Verify passphrase is preserved
-        assert_eq!(loaded.passphrase, Some("my_secret_passphrase".to_string()));
+        assert_eq!(loaded.passphrase.as_deref(), Some("my_secret_passphrase"));
 
         cleanup_test_wallet();
     }
@@ -245,7 +555,7 @@ mod tests {
         cleanup_test_wallet();
 
         let payload = WalletPayload {
-            mnemonic: "deterministic test".to_string(),
+            mnemonic: "deterministic test".to_string().into(),
             passphrase: None,
             network: Network::Testnet,
             fingerprint: "11111111".to_string(),
@@ -272,4 +582,584 @@ mod tests {
 
         cleanup_test_wallet();
     }
+
+    #[test]
+    fn test_change_password_roundtrip() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "change password test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "deadbeef".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let old_password = "old_password_123";
+        let new_password = "new_password_456";
+
+        save_wallet(&payload, old_password).unwrap();
+
+        change_password(old_password, new_password).unwrap();
+
+        // New password works and preserves the payload
+        let loaded = load_wallet(new_password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        // Old password no longer works
+        assert!(load_wallet(old_password).is_err());
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_change_password_wrong_old_password_leaves_file_untouched() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "untouched on failure".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "0badf00d".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "correct_password";
+        save_wallet(&payload, password).unwrap();
+
+        let result = change_password("wrong_password", "new_password");
+        assert!(matches!(result, Err(WalletError::InvalidPassword)));
+
+        // Original file is still readable with the original password
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_change_password_preserves_calibrated_kdf_and_aead() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "calibrated password change test".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "0ddba11".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let old_password = "old_password_calibrated";
+        let new_password = "new_password_calibrated";
+        let kdf = Argon2Params { time_cost: 2, memory_cost: 19456, parallelism: 1 };
+
+        save_wallet_with_params(&payload, old_password, &kdf, AeadAlgorithm::Aes256GcmSiv).unwrap();
+
+        change_password(old_password, new_password).unwrap();
+
+        let loaded = load_wallet(new_password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        // Re-encrypting under a new password must not reset a wallet that
+        // opted into stronger-than-default Argon2 parameters or GCM-SIV
+        // back to the defaults.
+        let file_bytes = fs::read(get_wallet_path().unwrap()).unwrap();
+        let (wallet_file, _): (WalletFile, usize) =
+            bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard()).unwrap();
+        assert_eq!(wallet_file.aead, AeadAlgorithm::Aes256GcmSiv);
+        assert_eq!(wallet_file.kdf.time_cost, kdf.time_cost);
+        assert_eq!(wallet_file.kdf.memory_cost, kdf.memory_cost);
+        assert_eq!(wallet_file.kdf.parallelism, kdf.parallelism);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_save_wallet_with_gcm_siv() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "gcm siv test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "cafebabe".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "gcm_siv_test_password";
+
+        save_wallet_with_params(&payload, password, &Argon2Params::default(), AeadAlgorithm::Aes256GcmSiv).unwrap();
+
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_save_wallet_calibrated_roundtrip() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "calibrated kdf test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "c0ffee00".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "calibrated_password";
+
+        // A tiny target keeps the test fast while still exercising the
+        // calibration path end-to-end.
+        save_wallet_calibrated(&payload, password, std::time::Duration::from_millis(1)).unwrap();
+
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_load_wallet_migrates_legacy_untagged_format() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "legacy format test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "feedface".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "legacy_password";
+
+        // Hand-build a v1 file exactly like pre-migration `save_wallet` did:
+        // implicit Argon2 defaults, hardcoded AES-256-GCM.
+        let mut salt = [0u8; 32];
+        OsRng.fill_bytes(&mut salt);
+        let key = EncryptionKey::from_password(password, &salt).unwrap();
+        let payload_bytes = bincode::serde::encode_to_vec(&payload, bincode::config::standard()).unwrap();
+        let encrypted = encrypt_with_algorithm(&payload_bytes, &key, AeadAlgorithm::Aes256Gcm).unwrap();
+        let nonce = encrypted[..12].to_vec();
+        let ciphertext_with_tag = encrypted[12..].to_vec();
+
+        let legacy_file = WalletFileV1 {
+            version: WalletFile::VERSION_LEGACY_UNTAGGED,
+            salt: salt.to_vec(),
+            nonce,
+            encrypted_data: ciphertext_with_tag[..ciphertext_with_tag.len() - 16].to_vec(),
+            auth_tag: ciphertext_with_tag[ciphertext_with_tag.len() - 16..].to_vec(),
+        };
+        let legacy_bytes = bincode::serde::encode_to_vec(&legacy_file, bincode::config::standard()).unwrap();
+        fs::write(get_wallet_path().unwrap(), legacy_bytes).unwrap();
+
+        // Reads transparently under the implicit legacy defaults
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        // The next save migrates it to the current tagged format
+        save_wallet(&loaded, password).unwrap();
+        let file_bytes = fs::read(get_wallet_path().unwrap()).unwrap();
+        let (header, _): (FileVersionHeader, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard()).unwrap();
+        assert_eq!(header.version, WalletFile::VERSION);
+
+        let reloaded = load_wallet(password).unwrap();
+        assert_eq!(reloaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_load_wallet_migrates_unsigned_v2_format() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "unsigned v2 format test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "0ff1ce00".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "unsigned_v2_password";
+
+        // Hand-build a v2 file exactly like pre-signature `save_wallet` did:
+        // tagged KDF/AEAD, but no content signature.
+        let mut salt = [0u8; 32];
+        OsRng.fill_bytes(&mut salt);
+        let kdf = Argon2Params::default();
+        let key = EncryptionKey::from_password_with_params(password, &salt, &kdf).unwrap();
+        let payload_bytes = bincode::serde::encode_to_vec(&payload, bincode::config::standard()).unwrap();
+        let encrypted = encrypt_with_algorithm(&payload_bytes, &key, AeadAlgorithm::Aes256Gcm).unwrap();
+        let nonce = encrypted[..12].to_vec();
+        let ciphertext_with_tag = encrypted[12..].to_vec();
+
+        let unsigned_file = WalletFileV2 {
+            version: WalletFile::VERSION_UNSIGNED,
+            kdf,
+            aead: AeadAlgorithm::Aes256Gcm,
+            salt: salt.to_vec(),
+            nonce,
+            encrypted_data: ciphertext_with_tag[..ciphertext_with_tag.len() - 16].to_vec(),
+            auth_tag: ciphertext_with_tag[ciphertext_with_tag.len() - 16..].to_vec(),
+        };
+        let unsigned_bytes = bincode::serde::encode_to_vec(&unsigned_file, bincode::config::standard()).unwrap();
+        fs::write(get_wallet_path().unwrap(), unsigned_bytes).unwrap();
+
+        // Reads transparently, with no signature to check
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        // The next save migrates it to the current signed format
+        save_wallet(&loaded, password).unwrap();
+        let file_bytes = fs::read(get_wallet_path().unwrap()).unwrap();
+        let (header, _): (FileVersionHeader, usize) = bincode::serde::decode_from_slice(&file_bytes, bincode::config::standard()).unwrap();
+        assert_eq!(header.version, WalletFile::VERSION);
+
+        let reloaded = load_wallet(password).unwrap();
+        assert_eq!(reloaded.mnemonic, payload.mnemonic);
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_load_wallet_rejects_substituted_ciphertext() {
+        cleanup_test_wallet();
+
+        let payload_a = WalletPayload {
+            mnemonic: "wallet a content".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "a0a0a0a0".to_string(),
+            created_at: Utc::now(),
+        };
+        let payload_b = WalletPayload {
+            mnemonic: "wallet b content entirely different".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "b0b0b0b0".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "shared_password";
+        let file_a = encode_wallet_file(&payload_a, password, &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+        let file_b = encode_wallet_file(&payload_b, password, &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+
+        // Splice wallet B's encrypted data into wallet A's file, keeping A's
+        // signature and fingerprint: a valid file swapped in from a
+        // different wallet, all under a shared password.
+        let (mut wallet_file_a, _): (WalletFile, usize) = bincode::serde::decode_from_slice(&file_a, bincode::config::standard()).unwrap();
+        let (wallet_file_b, _): (WalletFile, usize) = bincode::serde::decode_from_slice(&file_b, bincode::config::standard()).unwrap();
+        wallet_file_a.salt = wallet_file_b.salt;
+        wallet_file_a.nonce = wallet_file_b.nonce;
+        wallet_file_a.encrypted_data = wallet_file_b.encrypted_data;
+        wallet_file_a.auth_tag = wallet_file_b.auth_tag;
+        let spliced_bytes = bincode::serde::encode_to_vec(&wallet_file_a, bincode::config::standard()).unwrap();
+        fs::write(get_wallet_path().unwrap(), spliced_bytes).unwrap();
+
+        let result = load_wallet(password);
+        assert!(result.is_err());
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_load_wallet_from_path_expecting_rejects_complete_foreign_file() {
+        let payload_a = WalletPayload {
+            mnemonic: "wallet a content".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "a0a0a0a0".to_string(),
+            created_at: Utc::now(),
+        };
+        let payload_b = WalletPayload {
+            mnemonic: "wallet b content entirely different".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "b0b0b0b0".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let seed_a = mnemonic_to_seed(&payload_a.mnemonic, None).unwrap();
+        let expected_fingerprint = MasterKey::from_seed(seed_a.as_bytes(), payload_a.network).unwrap().fingerprint_bytes();
+
+        let password = "shared_password";
+        let file_b = encode_wallet_file(&payload_b, password, &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+
+        // Wallet B's file is entirely self-consistent — its own ciphertext,
+        // signature, and fingerprint — so `load_wallet_from_path` alone
+        // would accept it. Pinning to wallet A's expected fingerprint must
+        // still reject it, since B's signature never recovers to A.
+        let backup_path = std::env::temp_dir().join("cold_usb_test_expecting_rejects_foreign.enc");
+        fs::write(&backup_path, &file_b).unwrap();
+
+        assert!(load_wallet_from_path(&backup_path, password).is_ok());
+
+        let result = load_wallet_from_path_expecting(&backup_path, password, expected_fingerprint);
+        assert!(result.is_err());
+
+        fs::remove_file(&backup_path).unwrap();
+    }
+
+    #[test]
+    fn test_export_wallet_roundtrip() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "export roundtrip test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "01234567".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "export_password";
+        save_wallet(&payload, password).unwrap();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_export_roundtrip.enc");
+        let _ = fs::remove_file(&backup_path);
+
+        export_wallet(backup_path.clone(), password).unwrap();
+
+        let loaded = load_wallet_from_path(&backup_path, password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_export_wallet_wrong_password() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "export wrong password test".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "89abcdef".to_string(),
+            created_at: Utc::now(),
+        };
+
+        save_wallet(&payload, "correct_password").unwrap();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_export_wrong_password.enc");
+        let _ = fs::remove_file(&backup_path);
+
+        let result = export_wallet(backup_path.clone(), "wrong_password");
+        assert!(result.is_err());
+        assert!(!backup_path.exists());
+
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_export_import_wallet_sealed_roundtrip() {
+        use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "sealed export roundtrip test mnemonic".to_string().into(),
+            passphrase: Some("seed passphrase".to_string().into()),
+            network: Network::Testnet,
+            fingerprint: "13579bdf".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "sealed_export_password";
+        save_wallet(&payload, password).unwrap();
+
+        let secp = Secp256k1::new();
+        let recipient_sk = SecretKey::from_slice(&[6u8; 32]).unwrap();
+        let recipient_pk = PublicKey::from_secret_key(&secp, &recipient_sk);
+
+        let sealed_path = std::env::temp_dir().join("cold_usb_test_export_sealed_roundtrip.bin");
+        let _ = fs::remove_file(&sealed_path);
+
+        export_wallet_sealed(sealed_path.clone(), password, &recipient_pk).unwrap();
+
+        let recovered = import_wallet_sealed(sealed_path.clone(), &recipient_sk).unwrap();
+        assert_eq!(recovered.mnemonic, payload.mnemonic);
+        assert_eq!(recovered.fingerprint, payload.fingerprint);
+
+        fs::remove_file(&sealed_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_sealed_wrong_key_fails() {
+        use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "sealed wrong key test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "2468ace0".to_string(),
+            created_at: Utc::now(),
+        };
+
+        save_wallet(&payload, "sealed_password").unwrap();
+
+        let secp = Secp256k1::new();
+        let recipient_sk = SecretKey::from_slice(&[6u8; 32]).unwrap();
+        let recipient_pk = PublicKey::from_secret_key(&secp, &recipient_sk);
+        let wrong_sk = SecretKey::from_slice(&[7u8; 32]).unwrap();
+
+        let sealed_path = std::env::temp_dir().join("cold_usb_test_import_sealed_wrong_key.bin");
+        let _ = fs::remove_file(&sealed_path);
+
+        export_wallet_sealed(sealed_path.clone(), "sealed_password", &recipient_pk).unwrap();
+
+        let result = import_wallet_sealed(sealed_path.clone(), &wrong_sk);
+        assert!(result.is_err());
+
+        fs::remove_file(&sealed_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_roundtrip() {
+        cleanup_test_wallet();
+
+        let payload = WalletPayload {
+            mnemonic: "import roundtrip test mnemonic".to_string().into(),
+            passphrase: None,
+            network: Network::Testnet,
+            fingerprint: "10203040".to_string(),
+            created_at: Utc::now(),
+        };
+
+        let password = "import_password";
+        let backup_path = std::env::temp_dir().join("cold_usb_test_import_roundtrip.enc");
+        let _ = fs::remove_file(&backup_path);
+
+        // Build a standalone backup file without touching the default wallet path
+        let file_bytes = encode_wallet_file(
+            &WalletPayload { mnemonic: payload.mnemonic.clone(), passphrase: None, network: payload.network, fingerprint: payload.fingerprint.clone(), created_at: payload.created_at },
+            password,
+            &Argon2Params::default(),
+            AeadAlgorithm::default_for_new_wallets(),
+        ).unwrap();
+        fs::write(&backup_path, file_bytes).unwrap();
+
+        import_wallet(backup_path.clone(), password, false, None).unwrap();
+
+        let loaded = load_wallet(password).unwrap();
+        assert_eq!(loaded.mnemonic, payload.mnemonic);
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_refuses_existing_without_overwrite() {
+        cleanup_test_wallet();
+
+        let existing = WalletPayload {
+            mnemonic: "already here".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "aaaaaaaa".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&existing, "existing_password").unwrap();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_import_refuses_existing.enc");
+        let _ = fs::remove_file(&backup_path);
+        let incoming = WalletPayload {
+            mnemonic: "incoming backup".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "bbbbbbbb".to_string(),
+            created_at: Utc::now(),
+        };
+        let file_bytes = encode_wallet_file(&incoming, "incoming_password", &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+        fs::write(&backup_path, file_bytes).unwrap();
+
+        let result = import_wallet(backup_path.clone(), "incoming_password", false, None);
+        assert!(matches!(result, Err(WalletError::WalletExists)));
+
+        // Local wallet is untouched
+        let loaded = load_wallet("existing_password").unwrap();
+        assert_eq!(loaded.mnemonic, existing.mnemonic);
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_overwrite() {
+        cleanup_test_wallet();
+
+        let existing = WalletPayload {
+            mnemonic: "overwrite me".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "cccccccc".to_string(),
+            created_at: Utc::now(),
+        };
+        save_wallet(&existing, "existing_password").unwrap();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_import_overwrite.enc");
+        let _ = fs::remove_file(&backup_path);
+        let incoming = WalletPayload {
+            mnemonic: "incoming overwrite backup".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "dddddddd".to_string(),
+            created_at: Utc::now(),
+        };
+        let file_bytes = encode_wallet_file(&incoming, "incoming_password", &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+        fs::write(&backup_path, file_bytes).unwrap();
+
+        import_wallet(backup_path.clone(), "incoming_password", true, None).unwrap();
+
+        let loaded = load_wallet("incoming_password").unwrap();
+        assert_eq!(loaded.mnemonic, incoming.mnemonic);
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_rejects_undecryptable_backup() {
+        cleanup_test_wallet();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_import_wrong_password.enc");
+        let _ = fs::remove_file(&backup_path);
+        let incoming = WalletPayload {
+            mnemonic: "protected backup".to_string().into(),
+            passphrase: None,
+            network: Network::Bitcoin,
+            fingerprint: "eeeeeeee".to_string(),
+            created_at: Utc::now(),
+        };
+        let file_bytes = encode_wallet_file(&incoming, "right_password", &Argon2Params::default(), AeadAlgorithm::default_for_new_wallets()).unwrap();
+        fs::write(&backup_path, file_bytes).unwrap();
+
+        let result = import_wallet(backup_path.clone(), "wrong_password", false, None);
+        assert!(result.is_err());
+        assert!(!wallet_exists());
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
+
+    #[test]
+    fn test_import_wallet_rejects_corrupt_backup() {
+        cleanup_test_wallet();
+
+        let backup_path = std::env::temp_dir().join("cold_usb_test_import_corrupt.enc");
+        fs::write(&backup_path, b"not a wallet file").unwrap();
+
+        let result = import_wallet(backup_path.clone(), "any_password", false, None);
+        assert!(result.is_err());
+        assert!(!wallet_exists());
+
+        fs::remove_file(&backup_path).unwrap();
+        cleanup_test_wallet();
+    }
 }
@@ -5,9 +5,60 @@ use bitcoin::Network;
 use serde::{Deserialize, Serialize};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
-/// Wallet file structure (encrypted)
+use crate::crypto::encryption::{AeadAlgorithm, Argon2Params};
+use crate::crypto::secret::SecretString;
+
+/// Wallet file structure (encrypted), with a detached signature over its own
+/// salt/nonce/encrypted_data/version produced by the wallet's own master key
+/// (`crypto::keys::MasterKey::sign_content`). `load_wallet` recovers the
+/// signer's fingerprint from `content_sig` and checks it against the stored
+/// `signer_fingerprint` before attempting decryption, so a spliced file (some
+/// other wallet's ciphertext under this file's signature) is rejected up
+/// front rather than silently decrypting into the wrong payload. That
+/// self-check alone can't catch a *complete* foreign file — another
+/// wallet's ciphertext, signature, and fingerprint together, which is
+/// internally consistent on its own terms — since there's no independently-
+/// known fingerprint to hold the default wallet path to; callers who know
+/// which wallet they expect ahead of time (importing a named backup,
+/// pulling from a remote store) should verify with
+/// `storage::encrypted::load_wallet_from_path_expecting` instead.
 #[derive(Serialize, Deserialize)]
 pub struct WalletFile {
+    pub version: u32,
+    pub kdf: Argon2Params,
+    pub aead: AeadAlgorithm,
+    pub salt: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub encrypted_data: Vec<u8>,
+    pub auth_tag: Vec<u8>,
+    /// 65-byte recoverable ECDSA signature (compact sig || recovery id) over
+    /// `salt || nonce || encrypted_data || version`
+    pub content_sig: Vec<u8>,
+    /// BIP32 fingerprint of the key `content_sig` was recovered from
+    pub signer_fingerprint: [u8; 4],
+}
+
+/// Format version 2: KDF/AEAD parameters tagged into the file, but no
+/// content signature. Kept only so `load_wallet` can still open files
+/// written before `WalletFile::VERSION` was bumped to 3 — the next
+/// `save_wallet` rewrites them in the current signed format.
+#[derive(Serialize, Deserialize)]
+pub struct WalletFileV2 {
+    pub version: u32,
+    pub kdf: Argon2Params,
+    pub aead: AeadAlgorithm,
+    pub salt: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub encrypted_data: Vec<u8>,
+    pub auth_tag: Vec<u8>,
+}
+
+/// Format version 1: implicit Argon2 defaults and AES-256-GCM, with no
+/// tagging. Kept only so `load_wallet` can still open files written before
+/// `WalletFile::VERSION` was bumped past 1 — the next `save_wallet` rewrites
+/// them in the current format.
+#[derive(Serialize, Deserialize)]
+pub struct WalletFileV1 {
     pub version: u32,
     pub salt: Vec<u8>,
     pub nonce: Vec<u8>,
@@ -18,8 +69,8 @@ pub struct WalletFile {
 /// Wallet payload (decrypted)
 #[derive(Debug, Serialize, Deserialize, Zeroize, ZeroizeOnDrop)]
 pub struct WalletPayload {
-    pub mnemonic: String,
-    pub passphrase: Option<String>,
+    pub mnemonic: SecretString,
+    pub passphrase: Option<SecretString>,
     #[zeroize(skip)]
     pub network: Network,
     pub fingerprint: String,
@@ -29,17 +80,27 @@ pub struct WalletPayload {
 
 impl WalletFile {
     /// Current wallet file format version
-    pub const VERSION: u32 = 1;
+    pub const VERSION: u32 = 3;
+
+    /// Format version before KDF/AEAD parameters were tagged in the file
+    pub const VERSION_LEGACY_UNTAGGED: u32 = 1;
+
+    /// Format version with tagged KDF/AEAD parameters but no content signature
+    pub const VERSION_UNSIGNED: u32 = 2;
 
     /// Create new wallet file
     #[allow(dead_code)]
     pub fn new() -> Self {
         Self {
             version: Self::VERSION,
+            kdf: Argon2Params::default(),
+            aead: AeadAlgorithm::default_for_new_wallets(),
             salt: vec![],
             nonce: vec![],
             encrypted_data: vec![],
             auth_tag: vec![],
+            content_sig: vec![],
+            signer_fingerprint: [0u8; 4],
         }
     }
 }
@@ -12,6 +12,13 @@ pub struct WalletInfo {
     pub is_locked: bool,
 }
 
+/// Recovery phrase revealed by decrypting the wallet file on demand
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RevealedMnemonic {
+    pub mnemonic: String,
+    pub passphrase: Option<String>,
+}
+
 /// Address information
 #[derive(Serialize, Deserialize, Clone)]
 pub struct AddressInfo {
@@ -21,6 +28,15 @@ pub struct AddressInfo {
     pub public_key: String,
 }
 
+/// Watch-only account descriptors, safe to hand to an online tracking wallet
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WatchOnlyDescriptor {
+    pub receive: String,
+    pub change: String,
+    pub account_xpub: String,
+    pub fingerprint: String,
+}
+
 /// PSBT details for review
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PSBTDetails {
@@ -30,6 +46,25 @@ pub struct PSBTDetails {
     pub fee_rate: f64,
     pub total_input: u64,
     pub total_output: u64,
+    pub warnings: PSBTWarnings,
+}
+
+/// Safety warnings surfaced before signing a PSBT
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PSBTWarnings {
+    /// Output indices that claim to be change but whose script we couldn't re-derive
+    pub unverified_change: Vec<u32>,
+    /// Set if the computed fee exceeds the absolute or percent-of-spend cap
+    pub high_fee: Option<u64>,
+    /// Input indices whose previous output script doesn't belong to this wallet
+    pub non_wallet_inputs: Vec<u32>,
+}
+
+impl PSBTWarnings {
+    /// Whether any warning was raised and signing should require `force`
+    pub fn is_empty(&self) -> bool {
+        self.unverified_change.is_empty() && self.high_fee.is_none() && self.non_wallet_inputs.is_empty()
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -38,13 +73,23 @@ pub struct PSBTInput {
     pub vout: u32,
     pub amount: u64,
     pub address: Option<String>,
+    /// Set when an active output descriptor is configured and this input's
+    /// previous output script was matched against it
+    pub is_internal: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PSBTOutput {
     pub address: String,
     pub amount: u64,
+    /// Carries a BIP32/taproot derivation hint claiming to be our change
     pub is_change: bool,
+    /// `is_change` re-derived and byte-matched against this output's actual
+    /// scriptPubKey, so a forged derivation hint can't pass as verified
+    pub verified_ours: bool,
+    /// Set when an active output descriptor is configured and this output's
+    /// scriptPubKey was matched against it (receive or change branch)
+    pub is_internal: bool,
 }
 
 /// Signed PSBT result
@@ -1,21 +1,74 @@
 // Address derivation from HD wallet
 
-use bitcoin::bip32::Xpriv;
+use bitcoin::bip32::{ChildNumber, DerivationPath, Xpriv};
 use bitcoin::secp256k1::Secp256k1;
 use bitcoin::{Address, Network};
 use bitcoin::key::CompressedPublicKey;
 use crate::error::WalletError;
 use crate::types::AddressInfo;
 
-/// Script type for address generation
-#[derive(Debug, Clone, Copy)]
+/// Script type for address generation, one per BIP44/49/84/86 purpose
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ScriptType {
+    /// Legacy (P2PKH) - BIP44
+    Legacy,
+    /// Nested SegWit (P2SH-P2WPKH) - BIP49
+    NestedSegwit,
     /// Native SegWit (P2WPKH) - BIP84
     NativeSegwit,
     /// Taproot (P2TR) - BIP86
     Taproot,
 }
 
+impl ScriptType {
+    /// BIP purpose number this script type is derived under, e.g. 84 for Native SegWit
+    pub fn purpose(self) -> u32 {
+        match self {
+            ScriptType::Legacy => 44,
+            ScriptType::NestedSegwit => 49,
+            ScriptType::NativeSegwit => 84,
+            ScriptType::Taproot => 86,
+        }
+    }
+
+    /// Resolve the script type BIP44/49/84/86 standardizes for a purpose number
+    pub fn from_purpose(purpose: u32) -> Result<Self, WalletError> {
+        match purpose {
+            44 => Ok(ScriptType::Legacy),
+            49 => Ok(ScriptType::NestedSegwit),
+            84 => Ok(ScriptType::NativeSegwit),
+            86 => Ok(ScriptType::Taproot),
+            _ => Err(WalletError::InvalidDerivationPath(format!(
+                "Unsupported purpose: {} (expected 44, 49, 84, or 86)",
+                purpose
+            ))),
+        }
+    }
+}
+
+/// Derive P2PKH (Legacy) address from extended private key
+pub fn derive_p2pkh_address(
+    key: &Xpriv,
+    network: Network,
+) -> Result<Address, WalletError> {
+    let secp = Secp256k1::new();
+    let public_key = key.to_priv().public_key(&secp);
+
+    Ok(Address::p2pkh(public_key, network))
+}
+
+/// Derive P2SH-P2WPKH (Nested SegWit) address from extended private key
+pub fn derive_p2sh_p2wpkh_address(
+    key: &Xpriv,
+    network: Network,
+) -> Result<Address, WalletError> {
+    let secp = Secp256k1::new();
+    let public_key = key.to_priv().public_key(&secp);
+    let compressed_pubkey = CompressedPublicKey(public_key.inner);
+
+    Ok(Address::p2shwpkh(&compressed_pubkey, network))
+}
+
 /// Derive P2WPKH (Native SegWit) address from extended private key
 pub fn derive_p2wpkh_address(
     key: &Xpriv,
@@ -55,11 +108,15 @@ pub fn derive_address_from_key(
     let public_key = key.to_priv().public_key(&secp);
 
     let address = match script_type {
+        ScriptType::Legacy => derive_p2pkh_address(key, network)?,
+        ScriptType::NestedSegwit => derive_p2sh_p2wpkh_address(key, network)?,
         ScriptType::NativeSegwit => derive_p2wpkh_address(key, network)?,
         ScriptType::Taproot => derive_p2tr_address(key, network)?,
     };
 
     let script_type_str = match script_type {
+        ScriptType::Legacy => "p2pkh",
+        ScriptType::NestedSegwit => "p2sh-p2wpkh",
         ScriptType::NativeSegwit => "p2wpkh",
         ScriptType::Taproot => "p2tr",
     };
@@ -93,6 +150,38 @@ pub fn bip86_path(account: u32, change: u32, index: u32, network: Network) -> St
     format!("m/86'/{}'/{}'/{}/{}", coin_type, account, change, index)
 }
 
+/// Build the standard BIP44/49/84/86 derivation path for a script type, e.g.
+/// `m/84'/0'/0'/0/0`. The purpose component comes from `ScriptType::purpose`,
+/// so the path and the script type used to derive the address can never
+/// disagree the way they could when the script type was guessed separately
+/// from a path string.
+pub fn derivation_path(
+    script_type: ScriptType,
+    account: u32,
+    change: u32,
+    index: u32,
+    network: Network,
+) -> String {
+    let coin_type = match network {
+        Network::Bitcoin => 0,
+        _ => 1,
+    };
+    format!("m/{}'/{}'/{}'/{}/{}", script_type.purpose(), coin_type, account, change, index)
+}
+
+/// Infer the script type from a derivation path's purpose component
+/// (BIP44/49/84/86), falling back to Native SegWit for any other purpose so
+/// non-standard custom paths still derive an address instead of failing.
+pub fn script_type_from_path(path: &DerivationPath) -> ScriptType {
+    path.as_ref()
+        .first()
+        .map(|child| match child {
+            ChildNumber::Hardened { index } | ChildNumber::Normal { index } => *index,
+        })
+        .and_then(|purpose| ScriptType::from_purpose(purpose).ok())
+        .unwrap_or(ScriptType::NativeSegwit)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,10 +214,52 @@ mod tests {
         assert!(addr_str.starts_with("tb1p"));
     }
 
+    #[test]
+    fn test_derive_p2pkh_address() {
+        let key = create_test_key();
+        let address = derive_p2pkh_address(&key, Network::Testnet).unwrap();
+
+        let addr_str = address.to_string();
+        // Testnet P2PKH addresses start with "m" or "n"
+        assert!(addr_str.starts_with('m') || addr_str.starts_with('n'));
+    }
+
+    #[test]
+    fn test_derive_p2sh_p2wpkh_address() {
+        let key = create_test_key();
+        let address = derive_p2sh_p2wpkh_address(&key, Network::Testnet).unwrap();
+
+        let addr_str = address.to_string();
+        // Testnet P2SH addresses start with "2"
+        assert!(addr_str.starts_with('2'));
+    }
+
     #[test]
     fn test_derive_address_from_key() {
         let key = create_test_key();
 
+        // Test Legacy
+        let addr_info_legacy = derive_address_from_key(
+            &key,
+            ScriptType::Legacy,
+            "m/44'/1'/0'/0/0",
+            Network::Testnet,
+        ).unwrap();
+
+        assert!(addr_info_legacy.address.starts_with('m') || addr_info_legacy.address.starts_with('n'));
+        assert_eq!(addr_info_legacy.script_type, "p2pkh");
+
+        // Test Nested SegWit
+        let addr_info_nested = derive_address_from_key(
+            &key,
+            ScriptType::NestedSegwit,
+            "m/49'/1'/0'/0/0",
+            Network::Testnet,
+        ).unwrap();
+
+        assert!(addr_info_nested.address.starts_with('2'));
+        assert_eq!(addr_info_nested.script_type, "p2sh-p2wpkh");
+
         // Test Native SegWit
         let addr_info = derive_address_from_key(
             &key,
@@ -154,6 +285,47 @@ mod tests {
         assert_eq!(addr_info_tr.script_type, "p2tr");
     }
 
+    #[test]
+    fn test_script_type_purpose_roundtrip() {
+        for script_type in [ScriptType::Legacy, ScriptType::NestedSegwit, ScriptType::NativeSegwit, ScriptType::Taproot] {
+            assert_eq!(ScriptType::from_purpose(script_type.purpose()).unwrap(), script_type);
+        }
+
+        assert!(ScriptType::from_purpose(0).is_err());
+    }
+
+    #[test]
+    fn test_derivation_path_matches_purpose() {
+        assert_eq!(
+            derivation_path(ScriptType::Legacy, 0, 0, 0, Network::Bitcoin),
+            "m/44'/0'/0'/0/0"
+        );
+        assert_eq!(
+            derivation_path(ScriptType::NestedSegwit, 0, 1, 2, Network::Testnet),
+            "m/49'/1'/0'/1/2"
+        );
+        assert_eq!(
+            derivation_path(ScriptType::NativeSegwit, 0, 0, 0, Network::Bitcoin),
+            bip84_path(0, 0, 0, Network::Bitcoin)
+        );
+        assert_eq!(
+            derivation_path(ScriptType::Taproot, 0, 0, 0, Network::Bitcoin),
+            bip86_path(0, 0, 0, Network::Bitcoin)
+        );
+    }
+
+    #[test]
+    fn test_script_type_from_path() {
+        use crate::crypto::keys::parse_derivation_path;
+
+        assert_eq!(script_type_from_path(&parse_derivation_path("m/44'/0'/0'/0/0").unwrap()), ScriptType::Legacy);
+        assert_eq!(script_type_from_path(&parse_derivation_path("m/49'/0'/0'/0/0").unwrap()), ScriptType::NestedSegwit);
+        assert_eq!(script_type_from_path(&parse_derivation_path("m/84'/0'/0'/0/0").unwrap()), ScriptType::NativeSegwit);
+        assert_eq!(script_type_from_path(&parse_derivation_path("m/86'/0'/0'/0/0").unwrap()), ScriptType::Taproot);
+        // Non-standard purposes fall back to Native SegWit rather than failing
+        assert_eq!(script_type_from_path(&parse_derivation_path("m/0'/0'/0'/0/0").unwrap()), ScriptType::NativeSegwit);
+    }
+
     #[test]
     fn test_bip84_path() {
         let path = bip84_path(0, 0, 0, Network::Bitcoin);
@@ -0,0 +1,255 @@
+// Output descriptor parsing and address derivation (BIP380, via miniscript)
+
+use std::str::FromStr;
+
+use bitcoin::secp256k1::Secp256k1;
+use bitcoin::{Address, Network, ScriptBuf};
+use miniscript::descriptor::{DescriptorPublicKey, KeyMap};
+use miniscript::Descriptor;
+
+use crate::error::WalletError;
+use crate::types::AddressInfo;
+use crate::wallet::hd::descriptor_checksum;
+
+/// Split `desc#checksum` into its parts, verifying the checksum if present
+fn strip_and_verify_checksum(descriptor: &str) -> Result<&str, WalletError> {
+    match descriptor.split_once('#') {
+        Some((desc, checksum)) => {
+            let expected = descriptor_checksum(desc);
+            if checksum != expected {
+                return Err(WalletError::InvalidDerivationPath(format!(
+                    "Descriptor checksum mismatch: expected {}, got {}",
+                    expected, checksum
+                )));
+            }
+            Ok(desc)
+        }
+        None => Ok(descriptor),
+    }
+}
+
+/// Parse and sanity-check an output descriptor string
+pub fn parse_descriptor(descriptor: &str) -> Result<Descriptor<DescriptorPublicKey>, WalletError> {
+    let desc_str = strip_and_verify_checksum(descriptor.trim())?;
+
+    let desc = Descriptor::<DescriptorPublicKey>::from_str(desc_str)
+        .map_err(|e| WalletError::InvalidDerivationPath(format!("Invalid descriptor: {}", e)))?;
+
+    desc.sanity_check()
+        .map_err(|e| WalletError::InvalidDerivationPath(format!("Descriptor failed sanity check: {}", e)))?;
+
+    Ok(desc)
+}
+
+/// Same as [`parse_descriptor`], but also returns the key map for any private keys embedded in it
+#[allow(dead_code)]
+pub fn parse_descriptor_with_keymap(
+    descriptor: &str,
+) -> Result<(Descriptor<DescriptorPublicKey>, KeyMap), WalletError> {
+    let desc_str = strip_and_verify_checksum(descriptor.trim())?;
+
+    let (desc, keymap) = Descriptor::<DescriptorPublicKey>::parse_descriptor(&Secp256k1::new(), desc_str)
+        .map_err(|e| WalletError::InvalidDerivationPath(format!("Invalid descriptor: {}", e)))?;
+
+    Ok((desc, keymap))
+}
+
+/// Short label for the descriptor's top-level script type, for display purposes
+fn script_type_label(descriptor: &Descriptor<DescriptorPublicKey>) -> &'static str {
+    match descriptor {
+        Descriptor::Bare(_) => "bare",
+        Descriptor::Pkh(_) => "p2pkh",
+        Descriptor::Wpkh(_) => "p2wpkh",
+        Descriptor::Sh(_) => "p2sh",
+        Descriptor::Wsh(_) => "p2wsh",
+        Descriptor::Tr(_) => "p2tr",
+    }
+}
+
+/// Derive the concrete address at `index` for a (possibly multi-path) output descriptor
+pub fn derive_address_from_descriptor(
+    descriptor: &str,
+    index: u32,
+    network: Network,
+) -> Result<AddressInfo, WalletError> {
+    let desc = parse_descriptor(descriptor)?;
+    let secp = Secp256k1::new();
+
+    let derived = desc
+        .at_derivation_index(index)
+        .map_err(|e| WalletError::InvalidDerivationPath(format!("Cannot derive index {}: {}", index, e)))?;
+
+    let address = derived
+        .address(network)
+        .map_err(|e| WalletError::BitcoinError(format!("Descriptor has no address form: {}", e)))?;
+
+    // Single-key descriptors (wpkh/tr/pkh) resolve to one key; multisig/miniscript
+    // descriptors carry several, so `public_key` is left blank in that case.
+    let keys: Vec<String> = derived
+        .derived_descriptor(&secp)
+        .map(|d| {
+            let mut keys = Vec::new();
+            d.for_each_key(|k| {
+                keys.push(hex::encode(k.to_public_key().to_bytes()));
+                true
+            });
+            keys
+        })
+        .unwrap_or_default();
+    let public_key = if keys.len() == 1 { keys[0].clone() } else { String::new() };
+
+    Ok(AddressInfo {
+        address: address.to_string(),
+        derivation_path: format!("{}/{}", descriptor, index),
+        script_type: script_type_label(&desc).to_string(),
+        public_key,
+    })
+}
+
+/// A wallet configured from a receive/change output descriptor pair rather
+/// than only the mnemonic's implicit BIP44/49/84/86 paths, so multisig and
+/// other non-default script types can be derived and matched against the
+/// same way `HDWallet` handles single-key paths.
+pub struct DescriptorWallet {
+    receive: String,
+    change: String,
+    network: Network,
+}
+
+impl DescriptorWallet {
+    /// Parse and sanity-check a receive/change descriptor pair, e.g. the
+    /// output of `export_descriptor`/`account_descriptor`
+    pub fn new(receive: String, change: String, network: Network) -> Result<Self, WalletError> {
+        parse_descriptor(&receive)?;
+        parse_descriptor(&change)?;
+        Ok(Self { receive, change, network })
+    }
+
+    /// Derive the concrete address at `index` on the receive or change branch
+    pub fn derive_address(&self, index: u32, change: bool) -> Result<AddressInfo, WalletError> {
+        let descriptor = if change { &self.change } else { &self.receive };
+        derive_address_from_descriptor(descriptor, index, self.network)
+    }
+
+    /// Whether `script_pubkey` belongs to this wallet, scanning both the
+    /// receive and change branches up to `gap_limit` addresses each
+    pub fn address_belongs_to_wallet(&self, script_pubkey: &ScriptBuf, gap_limit: u32) -> bool {
+        for change in [false, true] {
+            for index in 0..gap_limit {
+                let matches = self
+                    .derive_address(index, change)
+                    .ok()
+                    .and_then(|info| Address::from_str(&info.address).ok())
+                    .and_then(|addr| addr.require_network(self.network).ok())
+                    .map(|addr| addr.script_pubkey() == *script_pubkey)
+                    .unwrap_or(false);
+                if matches {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Whether any key in the descriptor originates from the given master fingerprint
+pub fn descriptor_matches_fingerprint(
+    descriptor: &Descriptor<DescriptorPublicKey>,
+    fingerprint: [u8; 4],
+) -> bool {
+    let mut matches = false;
+    descriptor.for_each_key(|key| {
+        if let DescriptorPublicKey::XPub(xpub) = key {
+            if let Some((origin_fingerprint, _)) = &xpub.origin {
+                if *origin_fingerprint.as_bytes() == fingerprint {
+                    matches = true;
+                }
+            }
+        }
+        true
+    });
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WPKH_DESC: &str = "wpkh([73c5da0a/84'/1'/0']tpubDC5FSnBiZDMmhiuCmWAYsLwgLYrrT9rAqvTySfuCCrgsWz8wxMXUS9Tb9iVMvcRbvFcAHGkMD5Kx8koh4GquNGNTfohfk7pgjhaPCdXpoba/0/*)";
+
+    #[test]
+    fn test_parse_descriptor_without_checksum() {
+        let desc = parse_descriptor(WPKH_DESC).unwrap();
+        assert_eq!(script_type_label(&desc), "p2wpkh");
+    }
+
+    #[test]
+    fn test_parse_descriptor_with_valid_checksum() {
+        let with_checksum = crate::wallet::hd::with_checksum(WPKH_DESC);
+        assert!(parse_descriptor(&with_checksum).is_ok());
+    }
+
+    #[test]
+    fn test_parse_descriptor_with_invalid_checksum() {
+        let tampered = format!("{}#deadbeef", WPKH_DESC);
+        assert!(parse_descriptor(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_derive_address_from_descriptor() {
+        let info = derive_address_from_descriptor(WPKH_DESC, 0, Network::Testnet).unwrap();
+        assert_eq!(info.script_type, "p2wpkh");
+        assert!(info.address.starts_with("tb1q"));
+    }
+
+    #[test]
+    fn test_descriptor_matches_fingerprint() {
+        let desc = parse_descriptor(WPKH_DESC).unwrap();
+        assert!(descriptor_matches_fingerprint(&desc, [0x73, 0xc5, 0xda, 0x0a]));
+        assert!(!descriptor_matches_fingerprint(&desc, [0, 0, 0, 0]));
+    }
+
+    const WPKH_CHANGE_DESC: &str = "wpkh([73c5da0a/84'/1'/0']tpubDC5FSnBiZDMmhiuCmWAYsLwgLYrrT9rAqvTySfuCCrgsWz8wxMXUS9Tb9iVMvcRbvFcAHGkMD5Kx8koh4GquNGNTfohfk7pgjhaPCdXpoba/1/*)";
+
+    #[test]
+    fn test_descriptor_wallet_derive_address() {
+        let wallet =
+            DescriptorWallet::new(WPKH_DESC.to_string(), WPKH_CHANGE_DESC.to_string(), Network::Testnet).unwrap();
+        let receive = wallet.derive_address(0, false).unwrap();
+        let change = wallet.derive_address(0, true).unwrap();
+        assert_ne!(receive.address, change.address);
+    }
+
+    #[test]
+    fn test_descriptor_wallet_rejects_invalid_descriptor() {
+        let result = DescriptorWallet::new("not a descriptor".to_string(), WPKH_CHANGE_DESC.to_string(), Network::Testnet);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_descriptor_wallet_address_belongs_to_wallet() {
+        let wallet =
+            DescriptorWallet::new(WPKH_DESC.to_string(), WPKH_CHANGE_DESC.to_string(), Network::Testnet).unwrap();
+        let receive = wallet.derive_address(3, false).unwrap();
+        let script = Address::from_str(&receive.address)
+            .unwrap()
+            .require_network(Network::Testnet)
+            .unwrap()
+            .script_pubkey();
+
+        assert!(wallet.address_belongs_to_wallet(&script, 10));
+    }
+
+    #[test]
+    fn test_descriptor_wallet_address_not_belonging() {
+        let wallet =
+            DescriptorWallet::new(WPKH_DESC.to_string(), WPKH_CHANGE_DESC.to_string(), Network::Testnet).unwrap();
+        let foreign: ScriptBuf = Address::from_str("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx")
+            .unwrap()
+            .require_network(Network::Testnet)
+            .unwrap()
+            .script_pubkey();
+
+        assert!(!wallet.address_belongs_to_wallet(&foreign, 5));
+    }
+}
@@ -1,10 +1,11 @@
 // HD wallet implementation (BIP32/44)
 
 use bitcoin::Network;
-use bitcoin::bip32::{DerivationPath, Xpriv};
+use bitcoin::bip32::{DerivationPath, Xpriv, Xpub};
 use crate::crypto::keys::{MasterKey, parse_derivation_path};
 use crate::crypto::mnemonic::mnemonic_to_seed;
 use crate::error::WalletError;
+use crate::wallet::address::ScriptType;
 
 #[allow(dead_code)]
 pub struct HDWallet {
@@ -23,7 +24,7 @@ impl HDWallet {
         let seed = mnemonic_to_seed(mnemonic, passphrase)?;
 
         // Create master key from seed
-        let master_key = MasterKey::from_seed(&seed, network)?;
+        let master_key = MasterKey::from_seed(seed.as_bytes(), network)?;
 
         Ok(Self {
             master_key,
@@ -58,11 +59,124 @@ impl HDWallet {
         self.derive_key(&path)
     }
 
-    #[allow(dead_code)]
-    /// Get master key (for advanced operations)
+    /// Get master key (for advanced operations, e.g. `wallet::signer`)
     pub fn master_key(&self) -> &MasterKey {
         &self.master_key
     }
+
+    /// Derive the account-level extended public key (neutered) at the given path
+    pub fn account_xpub(&self, path: &DerivationPath) -> Result<Xpub, WalletError> {
+        self.master_key.account_xpub(path)
+    }
+
+    /// Build a watch-only descriptor pair (receive + change) for an account
+    ///
+    /// Returns `(account_xpub_with_origin, receive_descriptor, change_descriptor)`.
+    pub fn export_account_descriptors(
+        &self,
+        script_type: ScriptType,
+        account: u32,
+    ) -> Result<(String, String, String), WalletError> {
+        let account_path_str = account_derivation_path(script_type, account, self.network);
+        let path = parse_derivation_path(&account_path_str)?;
+        let account_xpub_with_origin = self.master_key.xpub_with_origin(&path)?;
+        let account_xpub = self.account_xpub(&path)?;
+
+        let origin = format!("[{}/{}]", self.fingerprint(), account_path_str.trim_start_matches("m/"));
+        let receive_body = format!("{}{}/0/*", origin, account_xpub);
+        let change_body = format!("{}{}/1/*", origin, account_xpub);
+
+        let receive = with_checksum(&wrap_descriptor(script_type, &receive_body));
+        let change = with_checksum(&wrap_descriptor(script_type, &change_body));
+
+        Ok((account_xpub_with_origin, receive, change))
+    }
+}
+
+/// Standard account-level derivation path for a script type, e.g. `m/84'/0'/0'`
+pub fn account_derivation_path(script_type: ScriptType, account: u32, network: Network) -> String {
+    let coin_type = match network {
+        Network::Bitcoin => 0,
+        _ => 1,
+    };
+    format!("m/{}'/{}'/{}'", script_type.purpose(), coin_type, account)
+}
+
+/// Wrap a descriptor body (origin + xpub + derivation suffix) in the output
+/// descriptor function for a script type, e.g. `pkh(...)` or `sh(wpkh(...))`
+fn wrap_descriptor(script_type: ScriptType, body: &str) -> String {
+    match script_type {
+        ScriptType::Legacy => format!("pkh({})", body),
+        ScriptType::NestedSegwit => format!("sh(wpkh({}))", body),
+        ScriptType::NativeSegwit => format!("wpkh({})", body),
+        ScriptType::Taproot => format!("tr({})", body),
+    }
+}
+
+/// Append a BIP380 descriptor checksum to a descriptor string, e.g. `wpkh(...)#qwerty01`
+pub fn with_checksum(descriptor: &str) -> String {
+    format!("{}#{}", descriptor, descriptor_checksum(descriptor))
+}
+
+/// Compute the BIP380 descriptor checksum (the same algorithm used by Bitcoin Core)
+pub(crate) fn descriptor_checksum(descriptor: &str) -> String {
+    const INPUT_CHARSET: &str =
+        "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+    const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+    fn poly_mod_step(c: u64, val: u64) -> u64 {
+        let c0 = c >> 35;
+        let mut c = ((c & 0x7ffffffff) << 5) ^ val;
+        if c0 & 1 != 0 {
+            c ^= 0xf5dee51989;
+        }
+        if c0 & 2 != 0 {
+            c ^= 0xa9fdca3312;
+        }
+        if c0 & 4 != 0 {
+            c ^= 0x1bab10e32d;
+        }
+        if c0 & 8 != 0 {
+            c ^= 0x3706b1677a;
+        }
+        if c0 & 16 != 0 {
+            c ^= 0x644d626ffd;
+        }
+        c
+    }
+
+    let mut c: u64 = 1;
+    let mut cls: u64 = 0;
+    let mut clscount: u32 = 0;
+
+    for ch in descriptor.chars() {
+        let pos = match INPUT_CHARSET.find(ch) {
+            Some(pos) => pos as u64,
+            None => continue,
+        };
+        c = poly_mod_step(c, pos & 31);
+        cls = cls * 3 + (pos >> 5);
+        clscount += 1;
+        if clscount == 3 {
+            c = poly_mod_step(c, cls);
+            cls = 0;
+            clscount = 0;
+        }
+    }
+    if clscount > 0 {
+        c = poly_mod_step(c, cls);
+    }
+    for _ in 0..8 {
+        c = poly_mod_step(c, 0);
+    }
+    c ^= 1;
+
+    let mut ret = String::with_capacity(8);
+    for j in 0..8 {
+        let idx = (c >> (5 * (7 - j))) & 31;
+        ret.push(CHECKSUM_CHARSET[idx as usize] as char);
+    }
+    ret
 }
 
 #[cfg(test)]
@@ -122,4 +236,60 @@ mod tests {
 
         assert_eq!(key1.to_priv().to_bytes(), key2.to_priv().to_bytes());
     }
+
+    #[test]
+    fn test_account_derivation_path() {
+        assert_eq!(
+            account_derivation_path(ScriptType::Legacy, 0, Network::Bitcoin),
+            "m/44'/0'/0'"
+        );
+        assert_eq!(
+            account_derivation_path(ScriptType::NestedSegwit, 1, Network::Testnet),
+            "m/49'/1'/1'"
+        );
+        assert_eq!(
+            account_derivation_path(ScriptType::NativeSegwit, 0, Network::Bitcoin),
+            "m/84'/0'/0'"
+        );
+        assert_eq!(
+            account_derivation_path(ScriptType::Taproot, 2, Network::Testnet),
+            "m/86'/1'/2'"
+        );
+    }
+
+    #[test]
+    fn test_export_account_descriptors_legacy_and_nested_segwit() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let wallet = HDWallet::from_mnemonic(mnemonic, None, Network::Testnet).unwrap();
+
+        let (_, legacy_receive, legacy_change) = wallet.export_account_descriptors(ScriptType::Legacy, 0).unwrap();
+        assert!(legacy_receive.starts_with("pkh("));
+        assert!(legacy_receive.contains("/0/*)"));
+        assert!(legacy_change.contains("/1/*)"));
+
+        let (_, nested_receive, _) = wallet.export_account_descriptors(ScriptType::NestedSegwit, 0).unwrap();
+        assert!(nested_receive.starts_with("sh(wpkh("));
+        assert!(nested_receive.contains("/0/*))"));
+    }
+
+    #[test]
+    fn test_descriptor_checksum_is_deterministic_and_well_formed() {
+        let desc = "wpkh([73c5da0a/84'/1'/0']tpubD6NzVbkrYhZ4WaWSyoBvQwbpLkojyoTZPRsgXELWz3Popb3qkNaNNYgsK9d3owpdnVnq";
+        let c1 = descriptor_checksum(desc);
+        let c2 = descriptor_checksum(desc);
+
+        assert_eq!(c1, c2);
+        assert_eq!(c1.len(), 8);
+        assert_ne!(c1, descriptor_checksum(&format!("{}/*)", desc)));
+    }
+
+    #[test]
+    fn test_with_checksum_appends_hash_separator() {
+        let desc = "wpkh(02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5)";
+        let full = with_checksum(desc);
+
+        assert!(full.starts_with(desc));
+        let (_, checksum) = full.split_once('#').unwrap();
+        assert_eq!(checksum.len(), 8);
+    }
 }
@@ -0,0 +1,314 @@
+// BIP322 "simple" message signing and verification
+//
+// Proves control of an address without broadcasting anything: a virtual
+// `to_spend` transaction commits to the message via a BIP340-style tagged
+// hash in its scriptSig, and a `to_sign` transaction spending it carries the
+// actual signature in its witness. Supports P2WPKH and P2TR addresses.
+
+use base64::{engine::general_purpose, Engine as _};
+use bitcoin::absolute::LockTime;
+use bitcoin::ecdsa;
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::key::{CompressedPublicKey, Keypair, XOnlyPublicKey};
+use bitcoin::script::{Builder, PushBytesBuf};
+use bitcoin::secp256k1::{schnorr, Message as Secp256k1Message, Secp256k1};
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Address, Amount, Network, OutPoint, PublicKey, ScriptBuf, Sequence, Transaction, TxIn, TxOut,
+    Txid, Witness,
+};
+
+use crate::error::WalletError;
+use crate::wallet::hd::HDWallet;
+
+/// BIP322 tagged hash of the message: `sha256(sha256(tag) || sha256(tag) || message)`
+fn message_tagged_hash(message: &[u8]) -> [u8; 32] {
+    let tag_hash = sha256::Hash::hash(b"BIP0322-signed-message");
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag_hash.as_byte_array());
+    engine.input(tag_hash.as_byte_array());
+    engine.input(message);
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// Build the virtual `to_spend` transaction: one input spending a synthetic
+/// all-zero outpoint whose scriptSig commits to the message, one zero-value
+/// output carrying the address's scriptPubKey
+fn build_to_spend(script_pubkey: &ScriptBuf, message: &[u8]) -> Result<Transaction, WalletError> {
+    let push = PushBytesBuf::try_from(message_tagged_hash(message).to_vec())
+        .map_err(|e| WalletError::SigningError(format!("Failed to build message commitment: {}", e)))?;
+    let script_sig = Builder::new()
+        .push_opcode(bitcoin::opcodes::OP_0)
+        .push_slice(push)
+        .into_script();
+
+    Ok(Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::all_zeros(),
+                vout: 0xFFFFFFFF,
+            },
+            script_sig,
+            sequence: Sequence(0),
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey: script_pubkey.clone(),
+        }],
+    })
+}
+
+/// Build the `to_sign` transaction: spends `to_spend`'s single output, carries the signature
+fn build_to_sign(to_spend_txid: Txid) -> Transaction {
+    Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: to_spend_txid,
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence(0),
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey: Builder::new()
+                .push_opcode(bitcoin::opcodes::all::OP_RETURN)
+                .into_script(),
+        }],
+    }
+}
+
+/// Script type implied by a derivation path, mirroring `derive_custom_address`'s heuristic
+fn script_type_is_taproot(derivation_path: &str) -> bool {
+    derivation_path.starts_with("m/86'") || derivation_path.starts_with("86'")
+}
+
+/// Sign `message` as the owner of the address derived at `derivation_path`, BIP322-simple style
+///
+/// The script type (P2WPKH vs P2TR) follows the same path-prefix convention as
+/// `derive_custom_address`. Returns the base64-encoded witness stack.
+pub fn sign_message(
+    wallet: &HDWallet,
+    message: &str,
+    derivation_path: &str,
+    network: Network,
+) -> Result<String, WalletError> {
+    let key = wallet.derive_key_from_path(derivation_path)?;
+    let secp = Secp256k1::new();
+    let public_key = key.to_priv().public_key(&secp);
+
+    let witness = if script_type_is_taproot(derivation_path) {
+        let keypair = Keypair::from_secret_key(&secp, &key.private_key);
+        let (x_only, _parity) = keypair.x_only_public_key();
+        let address = Address::p2tr(&secp, x_only, None, network);
+        let script_pubkey = address.script_pubkey();
+
+        let to_spend = build_to_spend(&script_pubkey, message.as_bytes())?;
+        let to_sign = build_to_sign(to_spend.compute_txid());
+
+        let prevouts = [TxOut {
+            value: Amount::from_sat(0),
+            script_pubkey,
+        }];
+        let mut sighash_cache = SighashCache::new(&to_sign);
+        let sighash = sighash_cache
+            .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), TapSighashType::All)
+            .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+
+        let tweaked = keypair.tap_tweak(&secp, None);
+        let msg = Secp256k1Message::from_digest(*sighash.as_ref());
+        let signature = secp.sign_schnorr_no_aux_rand(&msg, &tweaked.to_inner());
+
+        let mut witness = Witness::new();
+        witness.push(signature.as_ref());
+        witness
+    } else {
+        let compressed = CompressedPublicKey(public_key.inner);
+        let address = Address::p2wpkh(&compressed, network);
+        let script_pubkey = address.script_pubkey();
+
+        let to_spend = build_to_spend(&script_pubkey, message.as_bytes())?;
+        let to_sign = build_to_sign(to_spend.compute_txid());
+
+        let mut sighash_cache = SighashCache::new(&to_sign);
+        let sighash = sighash_cache
+            .p2wpkh_signature_hash(0, &script_pubkey, Amount::from_sat(0), EcdsaSighashType::All)
+            .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+
+        let msg = Secp256k1Message::from_digest(*sighash.as_ref());
+        let signature = secp.sign_ecdsa(&msg, &key.private_key);
+        let bitcoin_sig = ecdsa::Signature {
+            signature,
+            sighash_type: EcdsaSighashType::All,
+        };
+
+        let mut witness = Witness::new();
+        witness.push(bitcoin_sig.serialize());
+        witness.push(public_key.to_bytes());
+        witness
+    };
+
+    Ok(general_purpose::STANDARD.encode(witness.serialize()))
+}
+
+/// Verify a BIP322-simple `signature` (base64-encoded witness stack) of `message` for `address`
+pub fn verify_message(
+    message: &str,
+    address: &str,
+    signature: &str,
+    network: Network,
+) -> Result<bool, WalletError> {
+    let address = address
+        .parse::<Address<bitcoin::address::NetworkUnchecked>>()
+        .map_err(|e| WalletError::SigningError(format!("Invalid address: {}", e)))?
+        .require_network(network)
+        .map_err(|e| WalletError::SigningError(format!("Address does not match network: {}", e)))?;
+    let script_pubkey = address.script_pubkey();
+
+    let witness_bytes = general_purpose::STANDARD
+        .decode(signature)
+        .map_err(|e| WalletError::SigningError(format!("Invalid base64 signature: {}", e)))?;
+    let witness: Witness = bitcoin::consensus::deserialize(&witness_bytes)
+        .map_err(|e| WalletError::SigningError(format!("Invalid witness: {}", e)))?;
+
+    let to_spend = build_to_spend(&script_pubkey, message.as_bytes())?;
+    let mut to_sign = build_to_sign(to_spend.compute_txid());
+    to_sign.input[0].witness = witness.clone();
+
+    let secp = Secp256k1::new();
+
+    match witness.len() {
+        2 => {
+            let sig_bytes = &witness[0];
+            let pubkey_bytes = &witness[1];
+
+            let bitcoin_sig = match ecdsa::Signature::from_slice(sig_bytes) {
+                Ok(sig) => sig,
+                Err(_) => return Ok(false),
+            };
+            let public_key = match PublicKey::from_slice(pubkey_bytes) {
+                Ok(key) => key,
+                Err(_) => return Ok(false),
+            };
+            let compressed = match CompressedPublicKey::try_from(public_key) {
+                Ok(key) => key,
+                Err(_) => return Ok(false),
+            };
+            if Address::p2wpkh(&compressed, network).script_pubkey() != script_pubkey {
+                return Ok(false);
+            }
+
+            let mut sighash_cache = SighashCache::new(&to_sign);
+            let sighash = sighash_cache
+                .p2wpkh_signature_hash(0, &script_pubkey, Amount::from_sat(0), bitcoin_sig.sighash_type)
+                .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+            let msg = Secp256k1Message::from_digest(*sighash.as_ref());
+
+            Ok(secp
+                .verify_ecdsa(&msg, &bitcoin_sig.signature, &public_key.inner)
+                .is_ok())
+        }
+        1 => {
+            if !script_pubkey.is_p2tr() {
+                return Ok(false);
+            }
+            let sig_bytes = &witness[0];
+            let (sig_bytes, sighash_type) = match sig_bytes.len() {
+                64 => (sig_bytes, TapSighashType::Default),
+                65 => (
+                    &sig_bytes[..64],
+                    TapSighashType::from_consensus_u8(sig_bytes[64])
+                        .map_err(|e| WalletError::SigningError(format!("Invalid sighash byte: {}", e)))?,
+                ),
+                _ => return Ok(false),
+            };
+            let schnorr_sig = match schnorr::Signature::from_slice(sig_bytes) {
+                Ok(sig) => sig,
+                Err(_) => return Ok(false),
+            };
+
+            // The witness program already IS the tweaked output key for key-path spends
+            let program = &script_pubkey.as_bytes()[2..34];
+            let x_only = match XOnlyPublicKey::from_slice(program) {
+                Ok(key) => key,
+                Err(_) => return Ok(false),
+            };
+
+            let prevouts = [TxOut {
+                value: Amount::from_sat(0),
+                script_pubkey,
+            }];
+            let mut sighash_cache = SighashCache::new(&to_sign);
+            let sighash = sighash_cache
+                .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), sighash_type)
+                .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+            let msg = Secp256k1Message::from_digest(*sighash.as_ref());
+
+            Ok(secp.verify_schnorr(&schnorr_sig, &msg, &x_only).is_ok())
+        }
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_wallet() -> HDWallet {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        HDWallet::from_mnemonic(mnemonic, None, Network::Testnet).unwrap()
+    }
+
+    #[test]
+    fn test_sign_and_verify_p2wpkh_message() {
+        let wallet = test_wallet();
+        let path = "m/84'/1'/0'/0/0";
+        let key = wallet.derive_key_from_path(path).unwrap();
+        let secp = Secp256k1::new();
+        let public_key = key.to_priv().public_key(&secp);
+        let compressed = CompressedPublicKey(public_key.inner);
+        let address = Address::p2wpkh(&compressed, Network::Testnet);
+
+        let signature = sign_message(&wallet, "hello world", path, Network::Testnet).unwrap();
+
+        assert!(verify_message("hello world", &address.to_string(), &signature, Network::Testnet).unwrap());
+        assert!(!verify_message("goodbye", &address.to_string(), &signature, Network::Testnet).unwrap());
+    }
+
+    #[test]
+    fn test_sign_and_verify_p2tr_message() {
+        let wallet = test_wallet();
+        let path = "m/86'/1'/0'/0/0";
+        let key = wallet.derive_key_from_path(path).unwrap();
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_secret_key(&secp, &key.private_key);
+        let (x_only, _) = keypair.x_only_public_key();
+        let address = Address::p2tr(&secp, x_only, None, Network::Testnet);
+
+        let signature = sign_message(&wallet, "hello taproot", path, Network::Testnet).unwrap();
+
+        assert!(verify_message("hello taproot", &address.to_string(), &signature, Network::Testnet).unwrap());
+        assert!(!verify_message("wrong message", &address.to_string(), &signature, Network::Testnet).unwrap());
+    }
+
+    #[test]
+    fn test_verify_message_rejects_malformed_signature() {
+        let wallet = test_wallet();
+        let path = "m/84'/1'/0'/0/0";
+        let key = wallet.derive_key_from_path(path).unwrap();
+        let secp = Secp256k1::new();
+        let public_key = key.to_priv().public_key(&secp);
+        let compressed = CompressedPublicKey(public_key.inner);
+        let address = Address::p2wpkh(&compressed, Network::Testnet);
+
+        let result = verify_message("hello", &address.to_string(), "not-base64!!", Network::Testnet);
+        assert!(result.is_err());
+    }
+}
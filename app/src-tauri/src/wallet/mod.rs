@@ -0,0 +1,10 @@
+// Wallet domain logic (HD derivation, addresses, PSBT handling)
+
+pub mod address;
+pub mod descriptor;
+pub mod hd;
+pub mod message;
+pub mod psbt;
+pub mod signer;
+pub mod ur;
+pub mod verify;
@@ -1,14 +1,23 @@
 // PSBT parsing and signing
 
-use bitcoin::psbt::Psbt;
-use bitcoin::{Address, Network, TxOut, PublicKey};
+use bitcoin::psbt::{Output as PsbtOutput, Psbt};
+use bitcoin::{Address, Network, ScriptBuf, TxOut};
+use bitcoin::bip32::DerivationPath;
 use bitcoin::consensus::encode::serialize_hex;
 use hex::FromHex;
 use base64::{Engine as _, engine::general_purpose};
+use miniscript::psbt::PsbtExt;
 
 use crate::error::WalletError;
 use crate::types::{PSBTDetails, SignedPSBTResult, PSBTInput as PSBTInputInfo, PSBTOutput};
+use crate::wallet::address::{
+    derive_p2pkh_address, derive_p2sh_p2wpkh_address, derive_p2tr_address, derive_p2wpkh_address,
+    script_type_from_path, ScriptType,
+};
+use crate::wallet::descriptor::{descriptor_matches_fingerprint, parse_descriptor, DescriptorWallet};
 use crate::wallet::hd::HDWallet;
+use crate::wallet::signer;
+use crate::wallet::verify::{verify_psbt, DEFAULT_GAP_LIMIT};
 
 /// Decode PSBT from base64 or hex string
 fn decode_psbt(psbt_data: &str, format: &str) -> Result<Psbt, WalletError> {
@@ -54,9 +63,70 @@ fn extract_address(output: &TxOut, network: Network) -> Option<String> {
         .map(|addr| addr.to_string())
 }
 
-/// Parse PSBT and extract details for review
-pub fn parse_psbt(psbt_data: &str, format: &str, network: Network) -> Result<PSBTDetails, WalletError> {
+/// Re-derive the scriptPubKey our wallet would produce at `path`, for whichever
+/// script type the path's BIP44/49/84/86 purpose indicates
+fn rederive_script_pubkey(
+    wallet: &HDWallet,
+    path: &DerivationPath,
+    network: Network,
+) -> Result<ScriptBuf, WalletError> {
+    let key = wallet.derive_key(path)?;
+    let address = match script_type_from_path(path) {
+        ScriptType::Legacy => derive_p2pkh_address(&key, network)?,
+        ScriptType::NestedSegwit => derive_p2sh_p2wpkh_address(&key, network)?,
+        ScriptType::NativeSegwit => derive_p2wpkh_address(&key, network)?,
+        ScriptType::Taproot => derive_p2tr_address(&key, network)?,
+    };
+    Ok(address.script_pubkey())
+}
+
+/// Whether a PSBT output's claimed derivation actually belongs to this wallet:
+/// re-derive the scriptPubKey for every (fingerprint, path) hint that matches
+/// our fingerprint and check it byte-matches `actual_script`. A forged
+/// derivation hint pointing at someone else's address will never match.
+fn output_ownership_verified(
+    psbt_output: &PsbtOutput,
+    actual_script: &ScriptBuf,
+    wallet: &HDWallet,
+    network: Network,
+) -> bool {
+    let our_fingerprint = wallet.fingerprint_bytes();
+
+    let bip32_paths = psbt_output
+        .bip32_derivation
+        .values()
+        .filter(|(fingerprint, _)| *fingerprint.as_bytes() == our_fingerprint)
+        .map(|(_, path)| path);
+
+    let taproot_paths = psbt_output
+        .tap_key_origins
+        .values()
+        .filter(|(_, (fingerprint, _))| *fingerprint.as_bytes() == our_fingerprint)
+        .map(|(_, (_, path))| path);
+
+    bip32_paths.chain(taproot_paths).any(|path| {
+        rederive_script_pubkey(wallet, path, network)
+            .map(|script| &script == actual_script)
+            .unwrap_or(false)
+    })
+}
+
+/// Parse PSBT and extract details for review, including safety warnings
+///
+/// When `descriptor_wallet` is set (see `WalletState::set_active_descriptor`),
+/// each input and output is additionally checked against its receive/change
+/// descriptors so multisig or other non-default script paths can be flagged
+/// as belonging to this wallet, not just the implicit BIP44/49/84/86 paths
+/// `verify_psbt`/`output_ownership_verified` already cover.
+pub fn parse_psbt(
+    psbt_data: &str,
+    format: &str,
+    network: Network,
+    wallet: &HDWallet,
+    descriptor_wallet: Option<&DescriptorWallet>,
+) -> Result<PSBTDetails, WalletError> {
     let psbt = decode_psbt(psbt_data, format)?;
+    let warnings = verify_psbt(&psbt, wallet, network, DEFAULT_GAP_LIMIT)?;
     let tx = &psbt.unsigned_tx;
 
     // Calculate total input amount
@@ -79,25 +149,35 @@ pub fn parse_psbt(psbt_data: &str, format: &str, network: Network) -> Result<PSB
 
         total_input += amount;
 
-        // Extract address if available
-        let address = input
+        // Previous output's scriptPubKey, if known, used both for address
+        // display and descriptor-ownership matching below
+        let prev_script = input
             .witness_utxo
             .as_ref()
-            .and_then(|utxo| extract_address(utxo, network))
+            .map(|utxo| utxo.script_pubkey.clone())
             .or_else(|| {
-                input.non_witness_utxo.as_ref().and_then(|tx| {
-                    extract_address(
-                        &tx.output[tx_input.previous_output.vout as usize],
-                        network,
-                    )
-                })
+                input
+                    .non_witness_utxo
+                    .as_ref()
+                    .map(|tx| tx.output[tx_input.previous_output.vout as usize].script_pubkey.clone())
             });
 
+        let address = prev_script
+            .as_ref()
+            .and_then(|script| Address::from_script(script, network).ok())
+            .map(|addr| addr.to_string());
+
+        let is_internal = descriptor_wallet
+            .zip(prev_script.as_ref())
+            .map(|(dw, script)| dw.address_belongs_to_wallet(script, DEFAULT_GAP_LIMIT))
+            .unwrap_or(false);
+
         inputs.push(PSBTInputInfo {
             txid: tx_input.previous_output.txid.to_string(),
             vout: tx_input.previous_output.vout,
             amount,
             address,
+            is_internal,
         });
     }
 
@@ -111,17 +191,28 @@ pub fn parse_psbt(psbt_data: &str, format: &str, network: Network) -> Result<PSB
 
         let address = extract_address(output, network).unwrap_or_else(|| "Unknown".to_string());
 
-        // Check if this is a change output (has BIP32 derivation in PSBT output info)
-        let is_change = psbt
-            .outputs
-            .get(idx)
-            .map(|psbt_out| !psbt_out.bip32_derivation.is_empty())
+        // An output "claims" to be change if it carries a BIP32/taproot derivation
+        // hint at all; `verified_ours` re-derives those hints and only holds if
+        // the resulting scriptPubKey actually matches this output, so a watch-only
+        // host can no longer disguise a theft output as change just by attaching
+        // a fake derivation path.
+        let psbt_out = psbt.outputs.get(idx);
+        let is_change = psbt_out
+            .map(|psbt_out| !psbt_out.bip32_derivation.is_empty() || !psbt_out.tap_key_origins.is_empty())
+            .unwrap_or(false);
+        let verified_ours = psbt_out
+            .map(|psbt_out| output_ownership_verified(psbt_out, &output.script_pubkey, wallet, network))
+            .unwrap_or(false);
+        let is_internal = descriptor_wallet
+            .map(|dw| dw.address_belongs_to_wallet(&output.script_pubkey, DEFAULT_GAP_LIMIT))
             .unwrap_or(false);
 
         outputs.push(PSBTOutput {
             address,
             amount,
             is_change,
+            verified_ours,
+            is_internal,
         });
     }
 
@@ -143,149 +234,70 @@ pub fn parse_psbt(psbt_data: &str, format: &str, network: Network) -> Result<PSB
         fee_rate,
         total_input,
         total_output,
+        warnings,
     })
 }
 
-/// Sign PSBT with HD wallet
+/// Sign PSBT with HD wallet, optionally scoped to an output descriptor
+///
+/// When `descriptor` is provided, it is parsed and checked against the wallet's
+/// fingerprint first so descriptor-driven (e.g. multisig) inputs are only signed
+/// when we actually hold a key in that descriptor. Per-input signing still matches
+/// on the PSBT's own `bip32_derivation` map, so this also covers wsh/multisig
+/// inputs that carry more than one of our keys.
 pub fn sign_psbt(
     psbt_data: &str,
     format: &str,
     wallet: &HDWallet,
+    descriptor: Option<&str>,
+    network: Network,
+    force: bool,
 ) -> Result<SignedPSBTResult, WalletError> {
-    let mut psbt = decode_psbt(psbt_data, format)?;
-    let secp = bitcoin::secp256k1::Secp256k1::new();
-
-    // Track if we signed anything
-    let mut signed_any = false;
-
-    // Manually sign each input by finding matching keys
-    for (input_index, input) in psbt.inputs.iter_mut().enumerate() {
-        // Check if we have derivation paths for this input
-        if input.bip32_derivation.is_empty() {
-            continue;
+    if let Some(descriptor) = descriptor {
+        let desc = parse_descriptor(descriptor)?;
+        if !descriptor_matches_fingerprint(&desc, wallet.fingerprint_bytes()) {
+            return Err(WalletError::SigningError(
+                "Descriptor does not contain a key for this wallet".to_string(),
+            ));
         }
+    }
 
-        // Try to sign with each derivation path
-        for (pubkey, (fingerprint, derivation)) in input.bip32_derivation.clone().iter() {
-            // Check if this key belongs to our wallet
-            if *fingerprint.as_bytes() != wallet.fingerprint_bytes() {
-                continue;
-            }
-
-            // Convert derivation path to string
-            let path_str = format!("m/{}", derivation);
-
-            // Derive the private key
-            let private_key = match wallet.derive_key_from_path(&path_str) {
-                Ok(key) => key,
-                Err(_) => continue,
-            };
-
-            // Verify the public key matches
-            let derived_pubkey = private_key.private_key.public_key(&secp);
-            if &derived_pubkey != pubkey {
-                continue;
-            }
-
-            // Get the sighash type (default to ALL if not specified)
-            let sighash_type = input.sighash_type.unwrap_or(bitcoin::sighash::TapSighashType::All.into());
-
-            // Compute sighash for this input
-            let tx = &psbt.unsigned_tx;
-
-            // For SegWit inputs, we need to compute the sighash
-            if let Some(witness_utxo) = &input.witness_utxo {
-                // This is a witness input (SegWit)
-                let mut sighash_cache = bitcoin::sighash::SighashCache::new(tx);
-
-                let sighash = match sighash_cache.p2wpkh_signature_hash(
-                    input_index,
-                    &witness_utxo.script_pubkey,
-                    witness_utxo.value,
-                    sighash_type.ecdsa_hash_ty().map_err(|e| {
-                        WalletError::SigningError(format!("Invalid sighash type: {}", e))
-                    })?,
-                ) {
-                    Ok(hash) => hash,
-                    Err(e) => {
-                        return Err(WalletError::SigningError(format!(
-                            "Failed to compute sighash: {}",
-                            e
-                        )));
-                    }
-                };
-
-                // Sign the sighash
-                let message = bitcoin::secp256k1::Message::from_digest(*sighash.as_ref());
-                let signature = secp.sign_ecdsa(&message, &private_key.private_key);
-
-                // Create bitcoin signature with sighash type
-                let bitcoin_sig = bitcoin::ecdsa::Signature {
-                    signature,
-                    sighash_type: sighash_type.ecdsa_hash_ty().map_err(|e| {
-                        WalletError::SigningError(format!("Invalid sighash type: {}", e))
-                    })?,
-                };
-
-                // Convert secp256k1::PublicKey to bitcoin::PublicKey
-                let bitcoin_pubkey = PublicKey::new(derived_pubkey);
-
-                // Add signature to partial_sigs
-                input.partial_sigs.insert(bitcoin_pubkey, bitcoin_sig);
-                signed_any = true;
-            } else if let Some(_non_witness_utxo) = &input.non_witness_utxo {
-                // Legacy transaction signing not fully implemented yet
-                // This would require different sighash computation
-                continue;
-            }
-        }
+    let mut psbt = decode_psbt(psbt_data, format)?;
+
+    let warnings = verify_psbt(&psbt, wallet, network, DEFAULT_GAP_LIMIT)?;
+    if !warnings.is_empty() && !force {
+        return Err(WalletError::SigningError(format!(
+            "Refusing to sign: unverified_change={:?}, high_fee={:?}, non_wallet_inputs={:?} (pass force=true to override)",
+            warnings.unverified_change, warnings.high_fee, warnings.non_wallet_inputs
+        )));
     }
 
-    if !signed_any {
+    let secp = bitcoin::secp256k1::Secp256k1::new();
+
+    // Derive and insert per-input signatures; the actual derivation-matching,
+    // pubkey verification, and ECDSA/Taproot signing lives in `wallet::signer`
+    // so it can be driven by a bare `MasterKey` independent of this command's
+    // network/descriptor/safety-check plumbing.
+    let signed_count = signer::sign_psbt_inputs(&mut psbt, wallet.master_key())?;
+    if signed_count == 0 {
         return Err(WalletError::SigningError(
             "No inputs could be signed with this wallet".to_string(),
         ));
     }
 
-    // Check if PSBT is finalized
-    let mut is_finalized = true;
-    for input in &psbt.inputs {
-        if input.final_script_sig.is_none() && input.final_script_witness.is_none() {
-            is_finalized = false;
-            break;
-        }
-    }
-
-    // Try to finalize each input if not already finalized
-    if !is_finalized {
-        for input in psbt.inputs.iter_mut() {
-            if input.final_script_sig.is_some() || input.final_script_witness.is_some() {
-                continue;
-            }
-
-            // Try to finalize if we have signatures
-            if !input.partial_sigs.is_empty() {
-                // For P2WPKH, create witness
-                if let Some(witness_utxo) = &input.witness_utxo {
-                    if witness_utxo.script_pubkey.is_p2wpkh() {
-                        // Get the signature and pubkey
-                        if let Some((pubkey, sig)) = input.partial_sigs.iter().next() {
-                            let mut witness = bitcoin::Witness::new();
-                            witness.push(sig.serialize());
-                            witness.push(pubkey.to_bytes());
-                            input.final_script_witness = Some(witness);
-                            input.final_script_sig = Some(bitcoin::ScriptBuf::new());
-                        }
-                    }
-                }
-            }
-        }
-
-        // Re-check finalization status
-        is_finalized = psbt.inputs.iter().all(|input| {
-            input.final_script_sig.is_some() || input.final_script_witness.is_some()
-        });
-    }
+    // Delegate finalization to miniscript: its satisfier reads the partial_sigs
+    // and redeem/witness scripts we just populated and knows how to assemble
+    // final_script_sig/final_script_witness for legacy, P2SH-P2WPKH, P2WPKH,
+    // P2WSH, and threshold/miniscript policies alike, not just P2WPKH.
+    //
+    // Errors here just mean some inputs aren't fully signed yet (e.g. a
+    // multisig input still waiting on other cosigners), which isn't fatal;
+    // `is_finalized` below reflects which inputs actually finalized.
+    let _ = psbt.finalize_mut(&secp);
+
+    let is_finalized = psbt.inputs.iter().all(|input| {
+        input.final_script_sig.is_some() || input.final_script_witness.is_some()
+    });
 
     // Extract transaction hex if finalized
     let transaction_hex = if is_finalized {
@@ -0,0 +1,448 @@
+// Low-level PSBT signer driven directly by a MasterKey (BIP174)
+//
+// This is the core "sign offline, broadcast elsewhere" primitive: it knows
+// nothing about networks, descriptors, or the higher-level safety checks in
+// `wallet::verify` — just how to walk a PSBT's per-input derivation hints,
+// derive the matching private key from a bare `MasterKey`, and sign.
+// `wallet::psbt::sign_psbt` is the Tauri-facing wrapper that adds those
+// checks on top of this.
+
+use bitcoin::key::TapTweak;
+use bitcoin::psbt::Psbt;
+use bitcoin::secp256k1::{Keypair, Message, Secp256k1};
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::{ecdsa, taproot, PublicKey, TxOut};
+
+use crate::crypto::keys::MasterKey;
+use crate::error::WalletError;
+
+/// Sign every PSBT input whose `bip32_derivation`/`tap_key_origins` map
+/// contains an entry for `master_key`'s fingerprint.
+///
+/// For each matching entry, the corresponding private key is derived with
+/// `MasterKey::derive_path` and its public key is checked against the one
+/// recorded in the PSBT before signing — a derivation hint that doesn't
+/// actually correspond to the pubkey the input expects is skipped rather
+/// than trusted. ECDSA signatures land in `partial_sigs` (legacy/SegWit v0
+/// script types); Taproot key-path signatures land in `tap_key_sig`.
+/// Script-path Taproot spends (entries with a non-empty leaf hash set)
+/// aren't handled here.
+///
+/// Returns the number of inputs signed.
+pub fn sign_psbt_inputs(psbt: &mut Psbt, master_key: &MasterKey) -> Result<usize, WalletError> {
+    let secp = Secp256k1::new();
+    let mut signed = 0usize;
+
+    // BIP341 sighashes cover the whole prevout set, not just the input being
+    // signed, so build that snapshot once up front. `None` if any input is
+    // missing a witness_utxo/non_witness_utxo, in which case Taproot inputs
+    // below are skipped rather than treated as a hard error.
+    let per_input_prevouts: Vec<Option<TxOut>> = psbt
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, inp)| -> Result<Option<TxOut>, WalletError> {
+            if let Some(witness_utxo) = &inp.witness_utxo {
+                return Ok(Some(witness_utxo.clone()));
+            }
+            let Some(prev_tx) = &inp.non_witness_utxo else {
+                return Ok(None);
+            };
+            let vout = psbt.unsigned_tx.input[i].previous_output.vout as usize;
+            let prevout = prev_tx.output.get(vout).ok_or_else(|| {
+                WalletError::SigningError(format!(
+                    "non_witness_utxo for input {} has no output at vout {}",
+                    i, vout
+                ))
+            })?;
+            Ok(Some(prevout.clone()))
+        })
+        .collect::<Result<_, _>>()?;
+    let all_prevouts: Option<Vec<TxOut>> = per_input_prevouts.into_iter().collect();
+
+    if let Some(prevouts) = &all_prevouts {
+        let prevouts = Prevouts::All(prevouts.as_slice());
+
+        for (input_index, input) in psbt.inputs.iter_mut().enumerate() {
+            let internal_key = match input.tap_internal_key {
+                Some(key) => key,
+                None => continue,
+            };
+
+            let derivation = input
+                .tap_key_origins
+                .get(&internal_key)
+                .filter(|(leaf_hashes, _)| leaf_hashes.is_empty())
+                .filter(|(_, (fingerprint, _))| *fingerprint.as_bytes() == master_key.fingerprint_bytes())
+                .map(|(_, (_, path))| path.clone());
+
+            let path = match derivation {
+                Some(path) => path,
+                None => continue,
+            };
+
+            let xpriv = match master_key.derive_path(&path) {
+                Ok(xpriv) => xpriv,
+                Err(_) => continue,
+            };
+
+            let keypair = Keypair::from_secret_key(&secp, &xpriv.private_key.inner);
+            if keypair.x_only_public_key().0 != internal_key {
+                continue;
+            }
+
+            let sighash_type = input.sighash_type.unwrap_or(TapSighashType::All.into());
+            let tap_sighash_type = sighash_type
+                .taproot_hash_ty()
+                .map_err(|e| WalletError::SigningError(format!("Invalid sighash type: {}", e)))?;
+
+            let tweaked_keypair = keypair.tap_tweak(&secp, input.tap_merkle_root).to_inner();
+
+            let mut sighash_cache = SighashCache::new(&psbt.unsigned_tx);
+            let sighash = sighash_cache
+                .taproot_key_spend_signature_hash(input_index, &prevouts, tap_sighash_type)
+                .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+
+            let message = Message::from_digest(*sighash.as_ref());
+            let signature = secp.sign_schnorr(&message, &tweaked_keypair);
+
+            input.tap_key_sig = Some(taproot::Signature {
+                signature,
+                sighash_type: tap_sighash_type,
+            });
+            signed += 1;
+        }
+    }
+
+    for (input_index, input) in psbt.inputs.iter_mut().enumerate() {
+        if input.bip32_derivation.is_empty() {
+            continue;
+        }
+
+        for (pubkey, (fingerprint, path)) in input.bip32_derivation.clone().iter() {
+            if *fingerprint.as_bytes() != master_key.fingerprint_bytes() {
+                continue;
+            }
+
+            let xpriv = match master_key.derive_path(path) {
+                Ok(xpriv) => xpriv,
+                Err(_) => continue,
+            };
+
+            let derived_pubkey = xpriv.private_key.public_key(&secp);
+            if &derived_pubkey != pubkey {
+                continue;
+            }
+
+            let sighash_type = input.sighash_type.unwrap_or(TapSighashType::All.into());
+            let ecdsa_sighash_type: EcdsaSighashType = sighash_type
+                .ecdsa_hash_ty()
+                .map_err(|e| WalletError::SigningError(format!("Invalid sighash type: {}", e)))?;
+
+            let tx = &psbt.unsigned_tx;
+            let mut sighash_cache = SighashCache::new(tx);
+
+            let message = if let Some(witness_utxo) = &input.witness_utxo {
+                if witness_utxo.script_pubkey.is_p2wpkh() {
+                    let sighash = sighash_cache
+                        .p2wpkh_signature_hash(input_index, &witness_utxo.script_pubkey, witness_utxo.value, ecdsa_sighash_type)
+                        .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+                    Message::from_digest(*sighash.as_ref())
+                } else if let Some(witness_script) = &input.witness_script {
+                    let sighash = sighash_cache
+                        .segwit_v0_signature_hash(input_index, witness_script, witness_utxo.value, ecdsa_sighash_type)
+                        .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+                    Message::from_digest(*sighash.as_ref())
+                } else if let Some(redeem_script) = &input.redeem_script {
+                    // P2SH-wrapped P2WPKH: the redeem script is itself the witness program
+                    let sighash = sighash_cache
+                        .p2wpkh_signature_hash(input_index, redeem_script, witness_utxo.value, ecdsa_sighash_type)
+                        .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+                    Message::from_digest(*sighash.as_ref())
+                } else {
+                    // Taproot (handled separately above) or another script type we
+                    // don't know how to sign
+                    continue;
+                }
+            } else if let Some(non_witness_utxo) = &input.non_witness_utxo {
+                // The PSBT only binds this input to `previous_output.txid` via the
+                // unsigned transaction; nothing stops a malicious PSBT from attaching
+                // an unrelated `non_witness_utxo`, so its txid must match before its
+                // script/amount can be trusted for signing.
+                if non_witness_utxo.compute_txid() != tx.input[input_index].previous_output.txid {
+                    return Err(WalletError::SigningError(format!(
+                        "non_witness_utxo for input {} does not match previous_output.txid",
+                        input_index
+                    )));
+                }
+
+                let vout = tx.input[input_index].previous_output.vout as usize;
+                let prevout_script = &non_witness_utxo
+                    .output
+                    .get(vout)
+                    .ok_or_else(|| {
+                        WalletError::SigningError(format!(
+                            "non_witness_utxo for input {} has no output at vout {}",
+                            input_index, vout
+                        ))
+                    })?
+                    .script_pubkey;
+                let script_code = input.redeem_script.as_ref().unwrap_or(prevout_script);
+
+                let sighash = sighash_cache
+                    .legacy_signature_hash(input_index, script_code, ecdsa_sighash_type.to_u32())
+                    .map_err(|e| WalletError::SigningError(format!("Failed to compute sighash: {}", e)))?;
+                Message::from_digest(*sighash.as_ref())
+            } else {
+                continue;
+            };
+
+            let signature = secp.sign_ecdsa(&message, &xpriv.private_key.inner);
+            let bitcoin_sig = ecdsa::Signature {
+                signature,
+                sighash_type: ecdsa_sighash_type,
+            };
+
+            input.partial_sigs.insert(PublicKey::new(derived_pubkey), bitcoin_sig);
+            signed += 1;
+        }
+    }
+
+    Ok(signed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::bip32::{DerivationPath, Fingerprint};
+    use bitcoin::hashes::Hash;
+    use bitcoin::script::Builder;
+    use bitcoin::transaction::Version;
+    use bitcoin::{
+        Address, Amount, CompressedPublicKey, Network, OutPoint, ScriptBuf, Sequence, Transaction,
+        TxIn, Txid, Witness,
+    };
+    use std::str::FromStr;
+
+    #[test]
+    fn test_sign_psbt_inputs_no_matching_derivation() {
+        let master_key = MasterKey::from_seed(&[7u8; 64], Network::Testnet).unwrap();
+        let psbt = Psbt::from_unsigned_tx(bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![],
+            output: vec![],
+        })
+        .unwrap();
+        let mut psbt = psbt;
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 0);
+    }
+
+    /// An unsigned single-input, single-output tx spending an arbitrary outpoint,
+    /// for tests that only care about how `input[0]` gets signed.
+    fn unsigned_spend_tx() -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                previous_output: OutPoint { txid: Txid::all_zeros(), vout: 0 },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![TxOut {
+                value: Amount::from_sat(90_000),
+                script_pubkey: ScriptBuf::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sign_psbt_inputs_p2wpkh_roundtrip() {
+        let secp = Secp256k1::new();
+        let master_key = MasterKey::from_seed(&[1u8; 64], Network::Testnet).unwrap();
+        let path = DerivationPath::from_str("m/84'/1'/0'/0/0").unwrap();
+        let xpriv = master_key.derive_path(&path).unwrap();
+        let pubkey = xpriv.private_key.public_key(&secp);
+        let compressed = CompressedPublicKey(pubkey.inner);
+        let script_pubkey = Address::p2wpkh(&compressed, Network::Testnet).script_pubkey();
+
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_spend_tx()).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: script_pubkey.clone(),
+        });
+        psbt.inputs[0].bip32_derivation.insert(
+            pubkey,
+            (Fingerprint::from(master_key.fingerprint_bytes()), path),
+        );
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 1);
+
+        let (sig_pubkey, sig) = psbt.inputs[0].partial_sigs.iter().next().unwrap();
+        assert_eq!(*sig_pubkey, pubkey);
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = cache
+            .p2wpkh_signature_hash(0, &script_pubkey, Amount::from_sat(100_000), sig.sighash_type)
+            .unwrap();
+        let message = Message::from_digest(*sighash.as_ref());
+        assert!(secp.verify_ecdsa(&message, &sig.signature, &pubkey.inner).is_ok());
+    }
+
+    #[test]
+    fn test_sign_psbt_inputs_nested_segwit_roundtrip() {
+        let secp = Secp256k1::new();
+        let master_key = MasterKey::from_seed(&[2u8; 64], Network::Testnet).unwrap();
+        let path = DerivationPath::from_str("m/49'/1'/0'/0/0").unwrap();
+        let xpriv = master_key.derive_path(&path).unwrap();
+        let pubkey = xpriv.private_key.public_key(&secp);
+        let compressed = CompressedPublicKey(pubkey.inner);
+        let redeem_script = Address::p2wpkh(&compressed, Network::Testnet).script_pubkey();
+        let script_pubkey = Address::p2shwpkh(&compressed, Network::Testnet).script_pubkey();
+
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_spend_tx()).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: script_pubkey.clone(),
+        });
+        psbt.inputs[0].redeem_script = Some(redeem_script.clone());
+        psbt.inputs[0].bip32_derivation.insert(
+            pubkey,
+            (Fingerprint::from(master_key.fingerprint_bytes()), path),
+        );
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 1);
+
+        let (sig_pubkey, sig) = psbt.inputs[0].partial_sigs.iter().next().unwrap();
+        assert_eq!(*sig_pubkey, pubkey);
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = cache
+            .p2wpkh_signature_hash(0, &redeem_script, Amount::from_sat(100_000), sig.sighash_type)
+            .unwrap();
+        let message = Message::from_digest(*sighash.as_ref());
+        assert!(secp.verify_ecdsa(&message, &sig.signature, &pubkey.inner).is_ok());
+    }
+
+    #[test]
+    fn test_sign_psbt_inputs_p2wsh_roundtrip() {
+        let secp = Secp256k1::new();
+        let master_key = MasterKey::from_seed(&[3u8; 64], Network::Testnet).unwrap();
+        let path = DerivationPath::from_str("m/48'/1'/0'/0/0").unwrap();
+        let xpriv = master_key.derive_path(&path).unwrap();
+        let pubkey = xpriv.private_key.public_key(&secp);
+        let witness_script = Builder::new()
+            .push_key(&pubkey)
+            .push_opcode(bitcoin::opcodes::all::OP_CHECKSIG)
+            .into_script();
+        let script_pubkey = witness_script.to_p2wsh();
+
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_spend_tx()).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: script_pubkey.clone(),
+        });
+        psbt.inputs[0].witness_script = Some(witness_script.clone());
+        psbt.inputs[0].bip32_derivation.insert(
+            pubkey,
+            (Fingerprint::from(master_key.fingerprint_bytes()), path),
+        );
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 1);
+
+        let (sig_pubkey, sig) = psbt.inputs[0].partial_sigs.iter().next().unwrap();
+        assert_eq!(*sig_pubkey, pubkey);
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = cache
+            .segwit_v0_signature_hash(0, &witness_script, Amount::from_sat(100_000), sig.sighash_type)
+            .unwrap();
+        let message = Message::from_digest(*sighash.as_ref());
+        assert!(secp.verify_ecdsa(&message, &sig.signature, &pubkey.inner).is_ok());
+    }
+
+    #[test]
+    fn test_sign_psbt_inputs_legacy_roundtrip() {
+        let secp = Secp256k1::new();
+        let master_key = MasterKey::from_seed(&[4u8; 64], Network::Testnet).unwrap();
+        let path = DerivationPath::from_str("m/44'/1'/0'/0/0").unwrap();
+        let xpriv = master_key.derive_path(&path).unwrap();
+        let pubkey = xpriv.private_key.public_key(&secp);
+        let script_pubkey = Address::p2pkh(pubkey, Network::Testnet).script_pubkey();
+
+        let prev_tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut { value: Amount::from_sat(100_000), script_pubkey: script_pubkey.clone() }],
+        };
+        let prev_txid = prev_tx.compute_txid();
+
+        let mut unsigned_tx = unsigned_spend_tx();
+        unsigned_tx.input[0].previous_output = OutPoint { txid: prev_txid, vout: 0 };
+
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_tx).unwrap();
+        psbt.inputs[0].non_witness_utxo = Some(prev_tx);
+        psbt.inputs[0].bip32_derivation.insert(
+            pubkey,
+            (Fingerprint::from(master_key.fingerprint_bytes()), path),
+        );
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 1);
+
+        let (sig_pubkey, sig) = psbt.inputs[0].partial_sigs.iter().next().unwrap();
+        assert_eq!(*sig_pubkey, pubkey);
+
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = cache
+            .legacy_signature_hash(0, &script_pubkey, sig.sighash_type.to_u32())
+            .unwrap();
+        let message = Message::from_digest(*sighash.as_ref());
+        assert!(secp.verify_ecdsa(&message, &sig.signature, &pubkey.inner).is_ok());
+    }
+
+    #[test]
+    fn test_sign_psbt_inputs_taproot_roundtrip() {
+        let secp = Secp256k1::new();
+        let master_key = MasterKey::from_seed(&[5u8; 64], Network::Testnet).unwrap();
+        let path = DerivationPath::from_str("m/86'/1'/0'/0/0").unwrap();
+        let xpriv = master_key.derive_path(&path).unwrap();
+        let keypair = Keypair::from_secret_key(&secp, &xpriv.private_key.inner);
+        let (internal_key, _parity) = keypair.x_only_public_key();
+        let script_pubkey = Address::p2tr(&secp, internal_key, None, Network::Testnet).script_pubkey();
+
+        let mut psbt = Psbt::from_unsigned_tx(unsigned_spend_tx()).unwrap();
+        psbt.inputs[0].witness_utxo = Some(TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: script_pubkey.clone(),
+        });
+        psbt.inputs[0].tap_internal_key = Some(internal_key);
+        psbt.inputs[0].tap_key_origins.insert(
+            internal_key,
+            (vec![], (Fingerprint::from(master_key.fingerprint_bytes()), path)),
+        );
+
+        let signed = sign_psbt_inputs(&mut psbt, &master_key).unwrap();
+        assert_eq!(signed, 1);
+
+        let sig = psbt.inputs[0].tap_key_sig.unwrap();
+        let prevouts = [psbt.inputs[0].witness_utxo.clone().unwrap()];
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = cache
+            .taproot_key_spend_signature_hash(0, &Prevouts::All(&prevouts), sig.sighash_type)
+            .unwrap();
+        let message = Message::from_digest(*sighash.as_ref());
+        let tweaked = keypair.tap_tweak(&secp, None).to_inner();
+        assert!(secp
+            .verify_schnorr(&sig.signature, &message, &tweaked.x_only_public_key().0)
+            .is_ok());
+    }
+}
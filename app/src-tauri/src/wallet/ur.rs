@@ -0,0 +1,588 @@
+// Multi-part animated QR transport, in the spirit of BC-UR (BCR-2020-005/006)
+//
+// A single QR code tops out at a few KB, far short of a multi-input PSBT.
+// This module splits a payload into fixed-size source fragments and emits a
+// rateless stream of fountain-coded parts: each part XORs together a
+// pseudo-randomly chosen subset of fragments, so a scanner can reconstruct
+// the original message from any sufficient subset of frames, in any order,
+// without a fixed part count. Each part is serialized as
+// `ur:<type>/<seq>-<seqLen>/<bytewords>`, where `<bytewords>` is a compact
+// word-oriented encoding of the part header (sequence info, message length,
+// checksum) and XORed payload. The same fountain scheme also drives animated
+// QR frames, base64-encoded instead of bytewords, for display as a sequence
+// of standalone QR codes rather than scanned UR text.
+
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::error::WalletError;
+
+/// UR type tag used for PSBT payloads
+pub const CRYPTO_PSBT_TYPE: &str = "crypto-psbt";
+
+/// CRC32 (IEEE 802.3) checksum, used to seed the fountain PRNG and to verify reassembly
+pub fn crc32(data: &[u8]) -> u32 {
+    const POLY: u32 = 0xEDB88320;
+    let mut crc = 0xFFFFFFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLY & mask);
+        }
+    }
+    !crc
+}
+
+/// Deterministic xoshiro256** PRNG, seeded via splitmix64
+struct Xoshiro256 {
+    s: [u64; 4],
+}
+
+impl Xoshiro256 {
+    fn new(seed: u64) -> Self {
+        let mut sm = seed;
+        let mut next_sm = move || {
+            sm = sm.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = sm;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self {
+            s: [next_sm(), next_sm(), next_sm(), next_sm()],
+        }
+    }
+
+    fn rotl(x: u64, k: u32) -> u64 {
+        (x << k) | (x >> (64 - k))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let result = Self::rotl(self.s[1].wrapping_mul(5), 7).wrapping_mul(9);
+        let t = self.s[1] << 17;
+
+        self.s[2] ^= self.s[0];
+        self.s[3] ^= self.s[1];
+        self.s[1] ^= self.s[2];
+        self.s[0] ^= self.s[3];
+        self.s[2] ^= t;
+        self.s[3] = Self::rotl(self.s[3], 45);
+
+        result
+    }
+
+    /// Uniform value in `[0, bound)`
+    fn next_below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            0
+        } else {
+            self.next_u64() % bound
+        }
+    }
+}
+
+/// Degree sampled from a Robust-Soliton-like distribution over `1..=n`
+fn sample_degree(rng: &mut Xoshiro256, n: usize) -> usize {
+    if n <= 1 {
+        return 1;
+    }
+    let r = rng.next_below(1_000_000) as f64 / 1_000_000.0;
+
+    // Ideal soliton: rho(1) = 1/n, rho(d) = 1/(d*(d-1)) for d in 2..=n
+    let mut cumulative = 1.0 / n as f64;
+    if r <= cumulative {
+        return 1;
+    }
+    for d in 2..=n {
+        cumulative += 1.0 / (d as f64 * (d as f64 - 1.0));
+        if r <= cumulative {
+            return d;
+        }
+    }
+    n
+}
+
+/// Pick `degree` distinct fragment indices out of `n`, deterministically from `rng`
+fn sample_indices(rng: &mut Xoshiro256, n: usize, degree: usize) -> Vec<usize> {
+    let degree = degree.min(n);
+    let mut pool: Vec<usize> = (0..n).collect();
+    for i in 0..degree {
+        let j = i + rng.next_below((n - i) as u64) as usize;
+        pool.swap(i, j);
+    }
+    let mut chosen = pool[..degree].to_vec();
+    chosen.sort_unstable();
+    chosen
+}
+
+fn xor_into(target: &mut [u8], source: &[u8]) {
+    for (t, s) in target.iter_mut().zip(source.iter()) {
+        *t ^= s;
+    }
+}
+
+fn split_fragments(data: &[u8], fragment_len: usize) -> Vec<Vec<u8>> {
+    let mut fragments: Vec<Vec<u8>> = data
+        .chunks(fragment_len)
+        .map(|chunk| {
+            let mut fragment = chunk.to_vec();
+            fragment.resize(fragment_len, 0);
+            fragment
+        })
+        .collect();
+    if fragments.is_empty() {
+        fragments.push(vec![0u8; fragment_len]);
+    }
+    fragments
+}
+
+/// `[seq(4) | seq_len(4) | message_len(4) | checksum(4) | payload]`
+fn serialize_part_header(seq: u32, seq_len: u32, message_len: u32, checksum: u32, payload: &[u8]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16 + payload.len());
+    buf.extend_from_slice(&seq.to_be_bytes());
+    buf.extend_from_slice(&seq_len.to_be_bytes());
+    buf.extend_from_slice(&message_len.to_be_bytes());
+    buf.extend_from_slice(&checksum.to_be_bytes());
+    buf.extend_from_slice(payload);
+    buf
+}
+
+struct PartHeader {
+    seq: u32,
+    seq_len: u32,
+    message_len: u32,
+    checksum: u32,
+    payload: Vec<u8>,
+}
+
+fn deserialize_part_header(buf: &[u8]) -> Result<PartHeader, WalletError> {
+    if buf.len() < 16 {
+        return Err(WalletError::InvalidPSBT("UR part header truncated".to_string()));
+    }
+    Ok(PartHeader {
+        seq: u32::from_be_bytes(buf[0..4].try_into().unwrap()),
+        seq_len: u32::from_be_bytes(buf[4..8].try_into().unwrap()),
+        message_len: u32::from_be_bytes(buf[8..12].try_into().unwrap()),
+        checksum: u32::from_be_bytes(buf[12..16].try_into().unwrap()),
+        payload: buf[16..].to_vec(),
+    })
+}
+
+/// Compact word-oriented encoding (5 bits/char over a 32-symbol alphabet),
+/// used for the `<bytewords>` segment of each UR part
+const BYTEWORDS_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+
+fn bytewords_encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity((data.len() * 8).div_ceil(5));
+    let mut bits: u32 = 0;
+    let mut bit_count = 0u32;
+    for &byte in data {
+        bits = (bits << 8) | byte as u32;
+        bit_count += 8;
+        while bit_count >= 5 {
+            bit_count -= 5;
+            out.push(BYTEWORDS_ALPHABET[((bits >> bit_count) & 0x1f) as usize] as char);
+        }
+    }
+    if bit_count > 0 {
+        out.push(BYTEWORDS_ALPHABET[((bits << (5 - bit_count)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+fn bytewords_decode(s: &str) -> Result<Vec<u8>, WalletError> {
+    let mut bits: u32 = 0;
+    let mut bit_count = 0u32;
+    let mut out = Vec::new();
+    for ch in s.chars() {
+        let val = BYTEWORDS_ALPHABET
+            .iter()
+            .position(|&c| c as char == ch)
+            .ok_or_else(|| WalletError::InvalidPSBT(format!("Invalid bytewords character: {}", ch)))?
+            as u32;
+        bits = (bits << 5) | val;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push(((bits >> bit_count) & 0xff) as u8);
+        }
+    }
+    Ok(out)
+}
+
+fn format_part(ur_type: &str, seq: u32, seq_len: u32, header: &[u8]) -> String {
+    format!("ur:{}/{}-{}/{}", ur_type, seq, seq_len, bytewords_encode(header))
+}
+
+fn parse_part(part: &str) -> Result<String, WalletError> {
+    let rest = part
+        .strip_prefix("ur:")
+        .ok_or_else(|| WalletError::InvalidPSBT("Not a UR string".to_string()))?;
+    let (_ur_type, rest) = rest
+        .split_once('/')
+        .ok_or_else(|| WalletError::InvalidPSBT("Malformed UR part: missing type".to_string()))?;
+    let (_seq_info, bytewords) = rest
+        .split_once('/')
+        .ok_or_else(|| WalletError::InvalidPSBT("Malformed UR part: missing sequence info".to_string()))?;
+    Ok(bytewords.to_string())
+}
+
+/// Fountain-encode `data` into raw `[seq|seqLen|messageLen|checksum|payload]`
+/// headers, shared by both the BC-UR bytewords transport (`generate_ur_parts`)
+/// and the animated-QR base64 transport (`generate_qr_fountain_frames`).
+///
+/// Computes part `seq` on demand rather than precomputing a batch: `seq` runs
+/// `1..` with no upper bound, so a caller can keep pulling parts for as long
+/// as a scanner needs them, instead of being handed a fixed-size batch that
+/// may or may not have been enough.
+struct FountainHeaderStream {
+    fragments: Vec<Vec<u8>>,
+    fragment_len: usize,
+    message_len: u32,
+    checksum: u32,
+    seq: u32,
+}
+
+impl FountainHeaderStream {
+    fn new(data: &[u8], max_fragment_len: usize) -> Self {
+        let fragment_len = max_fragment_len.max(1);
+        Self {
+            fragments: split_fragments(data, fragment_len),
+            fragment_len,
+            message_len: data.len() as u32,
+            checksum: crc32(data),
+            seq: 0,
+        }
+    }
+}
+
+impl Iterator for FountainHeaderStream {
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Vec<u8>> {
+        self.seq += 1;
+        let n = self.fragments.len();
+
+        let mut rng = Xoshiro256::new(((self.checksum as u64) << 32) | self.seq as u64);
+        let degree = sample_degree(&mut rng, n);
+        let indices = sample_indices(&mut rng, n, degree);
+
+        let mut payload = vec![0u8; self.fragment_len];
+        for &idx in &indices {
+            xor_into(&mut payload, &self.fragments[idx]);
+        }
+
+        Some(serialize_part_header(self.seq, n as u32, self.message_len, self.checksum, &payload))
+    }
+}
+
+/// The unbounded fountain stream for `data`, one raw part header per `next()`
+/// call, computed on demand from sequence number `1` with no fixed end —
+/// callers that need more redundancy than an initial batch provided can keep
+/// pulling from the same stream rather than starting over.
+fn fountain_header_stream(data: &[u8], max_fragment_len: usize) -> FountainHeaderStream {
+    FountainHeaderStream::new(data, max_fragment_len)
+}
+
+/// How many parts a one-shot (non-streaming) caller gets by default: the
+/// source fragments plus a redundancy margin of XOR-combined parts, enough
+/// that a scanner looping through the batch can reassemble the message even
+/// if it misses a few frames.
+fn default_part_count(n: usize) -> usize {
+    n + (n / 2).max(3)
+}
+
+/// Split `data` into fountain-coded UR parts of the form `ur:<ur_type>/<seq>-<seqLen>/<bytewords>`
+pub fn generate_ur_parts(data: &[u8], ur_type: &str, max_fragment_len: usize) -> Vec<String> {
+    let stream = fountain_header_stream(data, max_fragment_len);
+    let part_count = default_part_count(stream.fragments.len());
+
+    let headers: Vec<Vec<u8>> = stream.take(part_count).collect();
+    let seq_len = headers.len() as u32;
+
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| format_part(ur_type, (i + 1) as u32, seq_len, header))
+        .collect()
+}
+
+/// Split `data` into fountain-coded frames for animated QR display, each one
+/// a base64 string of a `[seq|seqLen|messageLen|checksum|payload]` header —
+/// the same scheme as `generate_ur_parts`, but base64 instead of bytewords
+/// and without the `ur:type/seq-seqLen/` wrapper, since each frame is
+/// rendered standalone as its own QR code rather than scanned as UR text.
+pub fn generate_qr_fountain_frames(data: &[u8], max_fragment_len: usize) -> Vec<String> {
+    let stream = fountain_header_stream(data, max_fragment_len);
+    let part_count = default_part_count(stream.fragments.len());
+
+    stream
+        .take(part_count)
+        .map(|header| general_purpose::STANDARD.encode(header))
+        .collect()
+}
+
+/// Pull `count` more fountain-coded UR parts for `data`, continuing the
+/// sequence numbering at `start_seq` rather than restarting at 1 — for a
+/// caller (e.g. an animated-QR loop that's been told by a scanner it's still
+/// missing fragments) that wants additional redundancy beyond an initial
+/// batch without recomputing parts it already showed.
+pub fn generate_ur_parts_from(data: &[u8], ur_type: &str, max_fragment_len: usize, start_seq: u32, count: usize) -> Vec<String> {
+    fountain_header_stream(data, max_fragment_len)
+        .skip(start_seq.saturating_sub(1) as usize)
+        .take(count)
+        .enumerate()
+        .map(|(i, header)| format_part(ur_type, start_seq + i as u32, start_seq + count as u32 - 1, &header))
+        .collect()
+}
+
+/// Pull `count` more animated-QR fountain frames for `data`, continuing the
+/// sequence numbering at `start_seq` — the QR counterpart of
+/// `generate_ur_parts_from`, for a scanner loop that's cycled through an
+/// initial batch of frames without recovering every fragment.
+pub fn generate_qr_fountain_frames_from(data: &[u8], max_fragment_len: usize, start_seq: u32, count: usize) -> Vec<String> {
+    fountain_header_stream(data, max_fragment_len)
+        .skip(start_seq.saturating_sub(1) as usize)
+        .take(count)
+        .map(|header| general_purpose::STANDARD.encode(header))
+        .collect()
+}
+
+/// Reassemble the original bytes from a collection of raw fountain headers
+/// via belief-propagation peeling, shared by `decode_ur_parts` and
+/// `decode_qr_fountain_frames`.
+///
+/// Peels degree-1 parts to recover source fragments, substitutes recovered
+/// fragments back into higher-degree parts, and repeats until every fragment
+/// is known, then verifies the CRC32 before returning.
+fn decode_fountain_headers(headers: Vec<PartHeader>) -> Result<Vec<u8>, WalletError> {
+    if headers.is_empty() {
+        return Err(WalletError::InvalidPSBT("No fountain parts supplied".to_string()));
+    }
+
+    let mut seq_len: Option<usize> = None;
+    let mut message_len: Option<usize> = None;
+    let mut checksum: Option<u32> = None;
+    let mut known: Vec<Option<Vec<u8>>> = Vec::new();
+    let mut pending: Vec<(Vec<usize>, Vec<u8>)> = Vec::new();
+
+    for header in headers {
+        let n = *seq_len.get_or_insert(header.seq_len as usize);
+        if known.is_empty() {
+            known = vec![None; n];
+        }
+        message_len.get_or_insert(header.message_len as usize);
+        checksum.get_or_insert(header.checksum);
+
+        // Recompute the same fragment subset the encoder chose for this sequence number
+        let mut rng = Xoshiro256::new(((header.checksum as u64) << 32) | header.seq as u64);
+        let degree = sample_degree(&mut rng, n);
+        let indices = sample_indices(&mut rng, n, degree);
+
+        pending.push((indices, header.payload));
+    }
+
+    loop {
+        // Reduce every pending part using fragments already known
+        for (indices, payload) in pending.iter_mut() {
+            let mut i = 0;
+            while i < indices.len() {
+                let idx = indices[i];
+                if let Some(known_fragment) = &known[idx] {
+                    xor_into(payload, known_fragment);
+                    indices.remove(i);
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        // Peel any part that now resolves to exactly one unknown fragment
+        let mut progressed = false;
+        pending.retain(|(indices, payload)| match indices.len() {
+            0 => false,
+            1 => {
+                let idx = indices[0];
+                if known[idx].is_none() {
+                    known[idx] = Some(payload.clone());
+                    progressed = true;
+                }
+                false
+            }
+            _ => true,
+        });
+
+        if known.iter().all(|f| f.is_some()) {
+            break;
+        }
+        if !progressed {
+            return Err(WalletError::InvalidPSBT(
+                "Insufficient parts to reconstruct message".to_string(),
+            ));
+        }
+    }
+
+    let mut message: Vec<u8> = known.into_iter().flatten().flatten().collect();
+    let message_len = message_len.unwrap_or(message.len());
+    message.truncate(message_len);
+
+    if let Some(expected) = checksum {
+        if crc32(&message) != expected {
+            return Err(WalletError::InvalidPSBT("Fountain message checksum mismatch".to_string()));
+        }
+    }
+
+    Ok(message)
+}
+
+/// Reassemble the original bytes from a collection of UR parts via fountain decoding
+pub fn decode_ur_parts(parts: &[String]) -> Result<Vec<u8>, WalletError> {
+    let headers = parts
+        .iter()
+        .map(|part_str| {
+            let bytewords = parse_part(part_str)?;
+            let raw = bytewords_decode(&bytewords)?;
+            deserialize_part_header(&raw)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    decode_fountain_headers(headers)
+}
+
+/// Reassemble the original bytes from a collection of animated-QR fountain
+/// frames produced by `generate_qr_fountain_frames`
+pub fn decode_qr_fountain_frames(frames: &[String]) -> Result<Vec<u8>, WalletError> {
+    let headers = frames
+        .iter()
+        .map(|frame| {
+            let raw = general_purpose::STANDARD
+                .decode(frame)
+                .map_err(|e| WalletError::InvalidPSBT(format!("Invalid base64 QR frame: {}", e)))?;
+            deserialize_part_header(&raw)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    decode_fountain_headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crc32_known_value() {
+        // Standard CRC32 check value for the ASCII string "123456789"
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_bytewords_roundtrip() {
+        let data = b"the quick brown fox jumps over the lazy dog";
+        let encoded = bytewords_encode(data);
+        let decoded = bytewords_decode(&encoded).unwrap();
+        assert_eq!(&decoded[..data.len()], data);
+    }
+
+    #[test]
+    fn test_generate_and_decode_ur_parts_roundtrip() {
+        let data: Vec<u8> = (0..500u32).map(|i| (i % 256) as u8).collect();
+        let parts = generate_ur_parts(&data, CRYPTO_PSBT_TYPE, 40);
+
+        assert!(parts.iter().all(|p| p.starts_with("ur:crypto-psbt/")));
+
+        let decoded = decode_ur_parts(&parts).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_ur_parts_empty_is_error() {
+        assert!(decode_ur_parts(&[]).is_err());
+    }
+
+    #[test]
+    fn test_fountain_header_stream_is_unbounded() {
+        let data: Vec<u8> = (0..50u32).map(|i| (i % 256) as u8).collect();
+
+        // Pulling far more parts than any fixed redundancy margin would ever
+        // hand out proves the stream has no built-in ceiling
+        let headers: Vec<Vec<u8>> = fountain_header_stream(&data, 10).take(10_000).collect();
+        assert_eq!(headers.len(), 10_000);
+    }
+
+    #[test]
+    fn test_generate_ur_parts_from_continues_sequence_and_decodes() {
+        let data: Vec<u8> = (0..500u32).map(|i| (i % 256) as u8).collect();
+        let initial = generate_ur_parts(&data, CRYPTO_PSBT_TYPE, 40);
+
+        // Ask for more redundancy continuing right where the initial batch left off
+        let more = generate_ur_parts_from(&data, CRYPTO_PSBT_TYPE, 40, initial.len() as u32 + 1, 20);
+        assert_eq!(more.len(), 20);
+
+        let combined: Vec<String> = initial.into_iter().chain(more).collect();
+        let decoded = decode_ur_parts(&combined).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_small_subset_can_fail_gracefully() {
+        let data: Vec<u8> = (0..2000u32).map(|i| (i % 256) as u8).collect();
+        let parts = generate_ur_parts(&data, CRYPTO_PSBT_TYPE, 50);
+
+        // A single part is (almost always) not enough to recover 40 fragments
+        let result = decode_ur_parts(&parts[..1]);
+        assert!(result.is_err() || result.unwrap() == data);
+    }
+
+    #[test]
+    fn test_generate_and_decode_qr_fountain_frames_roundtrip() {
+        let data: Vec<u8> = (0..500u32).map(|i| (i % 256) as u8).collect();
+        let frames = generate_qr_fountain_frames(&data, 40);
+
+        // Every frame is plain base64, not the `ur:` text wrapper
+        assert!(frames.iter().all(|f| !f.starts_with("ur:")));
+        assert!(frames.iter().all(|f| general_purpose::STANDARD.decode(f).is_ok()));
+
+        let decoded = decode_qr_fountain_frames(&frames).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_generate_qr_fountain_frames_from_continues_sequence_and_decodes() {
+        let data: Vec<u8> = (0..500u32).map(|i| (i % 256) as u8).collect();
+        let initial = generate_qr_fountain_frames(&data, 40);
+
+        // Ask for more redundancy continuing right where the initial batch left off
+        let more = generate_qr_fountain_frames_from(&data, 40, initial.len() as u32 + 1, 20);
+        assert_eq!(more.len(), 20);
+
+        let combined: Vec<String> = initial.into_iter().chain(more).collect();
+        let decoded = decode_qr_fountain_frames(&combined).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decode_qr_fountain_frames_empty_is_error() {
+        assert!(decode_qr_fountain_frames(&[]).is_err());
+    }
+
+    #[test]
+    fn test_decode_qr_fountain_frames_rejects_invalid_base64() {
+        let result = decode_qr_fountain_frames(&["not valid base64!!".to_string()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_qr_fountain_frames_checksum_mismatch() {
+        let data: Vec<u8> = (0..200u32).map(|i| (i % 256) as u8).collect();
+        let mut frames = generate_qr_fountain_frames(&data, 40);
+
+        // Corrupt one frame's payload byte so reassembly no longer matches the checksum
+        let mut raw = general_purpose::STANDARD.decode(&frames[0]).unwrap();
+        let last = raw.len() - 1;
+        raw[last] ^= 0xFF;
+        frames[0] = general_purpose::STANDARD.encode(&raw);
+
+        let result = decode_qr_fountain_frames(&frames);
+        assert!(result.is_err() || result.unwrap() != data);
+    }
+}
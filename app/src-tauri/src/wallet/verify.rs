@@ -0,0 +1,175 @@
+// Safety verification of PSBTs before signing: change ownership + fee sanity
+//
+// An air-gapped signer is the last line of defense against a compromised
+// watch-only host, which can lie about which outputs are "change" or inflate
+// the fee. This module re-derives the wallet's own receive/change scripts and
+// cross-checks the PSBT against them instead of trusting its hints.
+
+use std::collections::HashSet;
+
+use bitcoin::psbt::Psbt;
+use bitcoin::{Network, ScriptBuf};
+
+use crate::error::WalletError;
+use crate::types::PSBTWarnings;
+use crate::wallet::address::{
+    derivation_path, derive_p2pkh_address, derive_p2sh_p2wpkh_address, derive_p2tr_address,
+    derive_p2wpkh_address, ScriptType,
+};
+use crate::wallet::hd::HDWallet;
+
+/// Default address-gap limit to scan when looking for wallet-owned change scripts
+pub const DEFAULT_GAP_LIMIT: u32 = 100;
+
+/// Absolute fee cap, in satoshis, above which signing is refused without `force`
+pub const ABSOLUTE_FEE_CAP_SATS: u64 = 1_000_000; // 0.01 BTC
+
+/// Fee as a fraction of the amount spent above which signing is refused without `force`
+pub const FEE_PERCENT_OF_SPEND_CAP: f64 = 0.10; // 10%
+
+/// Build the set of scriptPubkeys this wallet controls across receive+change
+/// chains, for every supported script type, up to `gap_limit` addresses per chain.
+fn wallet_script_set(
+    wallet: &HDWallet,
+    network: Network,
+    gap_limit: u32,
+) -> Result<HashSet<ScriptBuf>, WalletError> {
+    let mut scripts = HashSet::new();
+
+    for script_type in [
+        ScriptType::Legacy,
+        ScriptType::NestedSegwit,
+        ScriptType::NativeSegwit,
+        ScriptType::Taproot,
+    ] {
+        for change in 0..2u32 {
+            for index in 0..gap_limit {
+                let path = derivation_path(script_type, 0, change, index, network);
+                let key = wallet.derive_key_from_path(&path)?;
+                let address = match script_type {
+                    ScriptType::Legacy => derive_p2pkh_address(&key, network)?,
+                    ScriptType::NestedSegwit => derive_p2sh_p2wpkh_address(&key, network)?,
+                    ScriptType::NativeSegwit => derive_p2wpkh_address(&key, network)?,
+                    ScriptType::Taproot => derive_p2tr_address(&key, network)?,
+                };
+                scripts.insert(address.script_pubkey());
+            }
+        }
+    }
+
+    Ok(scripts)
+}
+
+/// Look up the scriptPubkey and amount an input spends, from whichever UTXO form is present
+fn input_prevout<'a>(
+    psbt: &'a Psbt,
+    input_index: usize,
+) -> (Option<&'a ScriptBuf>, Option<u64>) {
+    let input = &psbt.inputs[input_index];
+    let vout = psbt.unsigned_tx.input[input_index].previous_output.vout as usize;
+
+    if let Some(witness_utxo) = &input.witness_utxo {
+        return (Some(&witness_utxo.script_pubkey), Some(witness_utxo.value.to_sat()));
+    }
+    if let Some(non_witness_utxo) = &input.non_witness_utxo {
+        if let Some(out) = non_witness_utxo.output.get(vout) {
+            return (Some(&out.script_pubkey), Some(out.value.to_sat()));
+        }
+    }
+    (None, None)
+}
+
+/// Verify a PSBT's claimed change outputs and fee against the wallet, before signing
+pub fn verify_psbt(
+    psbt: &Psbt,
+    wallet: &HDWallet,
+    network: Network,
+    gap_limit: u32,
+) -> Result<PSBTWarnings, WalletError> {
+    let wallet_scripts = wallet_script_set(wallet, network, gap_limit)?;
+    let tx = &psbt.unsigned_tx;
+
+    // Change ownership: an output that hints at being ours (via BIP32 derivation
+    // or a taproot key origin) must match a script we actually derive.
+    let mut unverified_change = Vec::new();
+    for (idx, output) in tx.output.iter().enumerate() {
+        let psbt_output = match psbt.outputs.get(idx) {
+            Some(o) => o,
+            None => continue,
+        };
+
+        let claims_change =
+            !psbt_output.bip32_derivation.is_empty() || !psbt_output.tap_key_origins.is_empty();
+        if claims_change && !wallet_scripts.contains(&output.script_pubkey) {
+            unverified_change.push(idx as u32);
+        }
+    }
+
+    // Fee sanity and input ownership
+    let mut total_input: u64 = 0;
+    let mut all_amounts_known = true;
+    let mut non_wallet_inputs = Vec::new();
+
+    for idx in 0..psbt.inputs.len() {
+        let (script, amount) = input_prevout(psbt, idx);
+
+        match amount {
+            Some(amount) => total_input += amount,
+            None => all_amounts_known = false,
+        }
+
+        let is_ours = script.map(|s| wallet_scripts.contains(s)).unwrap_or(false);
+        if !is_ours {
+            non_wallet_inputs.push(idx as u32);
+        }
+    }
+
+    let total_output: u64 = tx.output.iter().map(|o| o.value.to_sat()).sum();
+
+    let high_fee = if all_amounts_known {
+        let fee = total_input.saturating_sub(total_output);
+        let exceeds_absolute = fee > ABSOLUTE_FEE_CAP_SATS;
+        let exceeds_percent =
+            total_output > 0 && (fee as f64) > (total_output as f64) * FEE_PERCENT_OF_SPEND_CAP;
+        if exceeds_absolute || exceeds_percent {
+            Some(fee)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    Ok(PSBTWarnings {
+        unverified_change,
+        high_fee,
+        non_wallet_inputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_script_set_covers_all_chains_and_types() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let wallet = HDWallet::from_mnemonic(mnemonic, None, Network::Testnet).unwrap();
+
+        let scripts = wallet_script_set(&wallet, Network::Testnet, 5).unwrap();
+
+        // 4 script types (legacy, nested segwit, native segwit, taproot) * 2 chains * 5 indices, all distinct
+        assert_eq!(scripts.len(), 40);
+    }
+
+    #[test]
+    fn test_wallet_script_set_is_deterministic() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let wallet = HDWallet::from_mnemonic(mnemonic, None, Network::Testnet).unwrap();
+
+        let scripts1 = wallet_script_set(&wallet, Network::Testnet, 10).unwrap();
+        let scripts2 = wallet_script_set(&wallet, Network::Testnet, 10).unwrap();
+
+        assert_eq!(scripts1, scripts2);
+    }
+}